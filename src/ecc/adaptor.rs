@@ -0,0 +1,354 @@
+use crate::ecc::constant_time::ConstantTimeEq;
+use crate::ecc::private_key::PrivateKey;
+use crate::ecc::s256_point::S256Point;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::schnorr::{challenge, has_even_y, SchnorrSignature};
+use crate::ecc::signature::Signature;
+use crate::ecc::uint::U256;
+use crate::ecc::x_only_public_key::{Parity, XOnlyPublicKey};
+use crate::ecc::zeroize::Zeroize;
+use crate::error::Error;
+use crate::hash::tagged_hash;
+
+// Schnorr signature encrypted to an adaptor point T = tG. Adding t to s gives
+// a valid BIP340 signature, and seeing that signature reveals t.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SchnorrAdaptorSignature {
+    // Full nonce R = kG + T. When it has odd y the final signature uses -R,
+    // so both k and t enter with a negative sign.
+    pub r: S256Point,
+    pub s: Scalar,
+}
+
+impl SchnorrAdaptorSignature {
+    pub fn encrypt(
+        key: &PrivateKey,
+        msg: &[u8],
+        adaptor: &S256Point,
+        aux_rand: &[u8; 32],
+    ) -> Result<Self, Error> {
+        let adaptor_bytes = adaptor.sec_compressed()?;
+
+        let (public_key, parity) = XOnlyPublicKey::from_point(&key.public_key())?;
+        let p_bytes = public_key.serialize();
+        let mut d = match parity {
+            Parity::Even => key.secret(),
+            Parity::Odd => -key.secret(),
+        };
+
+        let mut k = hedged_nonce(
+            "SchnorrAdaptor/nonce",
+            &d,
+            &[&p_bytes, &adaptor_bytes, msg],
+            aux_rand,
+        );
+
+        let r = S256Point::mul_generator(&k) + *adaptor;
+        let r_bytes = match r.x() {
+            Some(x) => x.to_be_bytes(),
+            None => {
+                k.zeroize();
+                d.zeroize();
+                return Err(Error::PointAtInfinity);
+            }
+        };
+        let e = challenge(&r_bytes, &p_bytes, msg);
+        let s = sign_of(&r) * k + e * d;
+        k.zeroize();
+        d.zeroize();
+
+        Ok(Self { r, s })
+    }
+
+    // s'G == ±(R - T) + eP, the sign matching the parity of R
+    pub fn verify(&self, public_key: &XOnlyPublicKey, msg: &[u8], adaptor: &S256Point) -> bool {
+        let r_bytes = match self.r.x() {
+            Some(x) => x.to_be_bytes(),
+            None => return false,
+        };
+        let e = challenge(&r_bytes, &public_key.serialize(), msg);
+
+        let expected = (self.r - *adaptor).mul_vartime(&sign_of(&self.r))
+            + public_key.to_point().mul_vartime(&e);
+
+        S256Point::mul_generator(&self.s).ct_eq(&expected)
+    }
+
+    pub fn decrypt(&self, secret: Scalar) -> SchnorrSignature {
+        SchnorrSignature {
+            r: self.r.x().expect("R isn't infinity"),
+            s: self.s + sign_of(&self.r) * secret,
+        }
+    }
+
+    // Extracts t from the completed signature, checking it against T
+    pub fn recover(&self, sig: &SchnorrSignature, adaptor: &S256Point) -> Result<Scalar, Error> {
+        let secret = sign_of(&self.r) * (sig.s - self.s);
+
+        if S256Point::mul_generator(&secret) != *adaptor {
+            return Err(Error::InvalidInput(
+                "Signature wasn't decrypted from this adaptor signature".to_string(),
+            ));
+        }
+
+        Ok(secret)
+    }
+}
+
+// ECDSA signature encrypted to Y = yG. The nonce point R = kY can't be checked
+// from s alone, so a DLEQ proof shows R and R' = kG share the same k.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct EcdsaAdaptorSignature {
+    pub r: S256Point,
+    pub r_hat: S256Point,
+    pub s: Scalar,
+    pub proof: DleqProof,
+}
+
+impl EcdsaAdaptorSignature {
+    pub fn encrypt(
+        key: &PrivateKey,
+        z: Scalar,
+        adaptor: &S256Point,
+        aux_rand: &[u8; 32],
+    ) -> Result<Self, Error> {
+        let adaptor_bytes = adaptor.sec_compressed()?;
+
+        let mut k = hedged_nonce(
+            "EcdsaAdaptor/nonce",
+            &key.secret(),
+            &[&z.to_be_bytes(), &adaptor_bytes],
+            aux_rand,
+        );
+
+        let r = adaptor.mul_ct(&k);
+        let r_hat = S256Point::mul_generator(&k);
+        let x = r.x().expect("k and the adaptor are non-zero").num();
+        let r_scalar = Scalar::new(x);
+        if r_scalar.is_zero() {
+            k.zeroize();
+            return Err(Error::InvalidInput(
+                "Nonce has an x coordinate of zero mod N".to_string(),
+            ));
+        }
+
+        let proof = DleqProof::prove(k, adaptor, &r_hat, &r, aux_rand);
+        let mut k_inv = k.invert();
+        let s = (z + r_scalar * key.secret()) * k_inv;
+        k.zeroize();
+        k_inv.zeroize();
+
+        Ok(Self { r, r_hat, s, proof })
+    }
+
+    pub fn verify(&self, public_key: &S256Point, z: Scalar, adaptor: &S256Point) -> bool {
+        let r_scalar = match self.r.x() {
+            Some(x) => Scalar::new(x.num()),
+            None => return false,
+        };
+
+        if r_scalar.is_zero() || self.s.is_zero() {
+            return false;
+        }
+
+        if !self.proof.verify(adaptor, &self.r_hat, &self.r) {
+            return false;
+        }
+
+        // s'^-1 (zG + rP) == R'
+        let s_inv = self.s.invert();
+        (S256Point::mul_generator(&(z * s_inv)) + public_key.mul_vartime(&(r_scalar * s_inv)))
+            .ct_eq(&self.r_hat)
+    }
+
+    // s = s' / y, since R = kY = (ky)G. The result is returned in low-s form.
+    pub fn decrypt(&self, secret: Scalar) -> Signature {
+        let r = Scalar::new(self.r.x().expect("R isn't infinity").num());
+
+        Signature::new(r, self.s * secret.invert()).normalize_s()
+    }
+
+    pub fn recover(&self, sig: &Signature, adaptor: &S256Point) -> Result<Scalar, Error> {
+        if sig.s.is_zero() {
+            return Err(Error::InvalidInput("Signature s is zero".to_string()));
+        }
+
+        // Normalizing s may have negated it, so y is either candidate
+        let candidate = self.s * sig.s.invert();
+        if S256Point::mul_generator(&candidate) == *adaptor {
+            Ok(candidate)
+        } else if S256Point::mul_generator(&-candidate) == *adaptor {
+            Ok(-candidate)
+        } else {
+            Err(Error::InvalidInput(
+                "Signature wasn't decrypted from this adaptor signature".to_string(),
+            ))
+        }
+    }
+}
+
+// Chaum-Pedersen proof that log_G(A) == log_Y(B)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DleqProof {
+    pub e: Scalar,
+    pub s: Scalar,
+}
+
+impl DleqProof {
+    pub fn prove(
+        mut secret: Scalar,
+        y: &S256Point,
+        a: &S256Point,
+        b: &S256Point,
+        aux_rand: &[u8; 32],
+    ) -> Self {
+        let mut nonce = hedged_nonce(
+            "DLEQ/nonce",
+            &secret,
+            &[&y.sec_or_zero(), &a.sec_or_zero(), &b.sec_or_zero()],
+            aux_rand,
+        );
+
+        let e = dleq_challenge(
+            y,
+            a,
+            b,
+            &S256Point::mul_generator(&nonce),
+            &y.mul_ct(&nonce),
+        );
+
+        let s = nonce + e * secret;
+        nonce.zeroize();
+        secret.zeroize();
+
+        Self { e, s }
+    }
+
+    pub fn verify(&self, y: &S256Point, a: &S256Point, b: &S256Point) -> bool {
+        // Rebuild the commitments: sG - eA and sY - eB
+        let commitment_g = S256Point::mul_generator(&self.s) - a.mul_vartime(&self.e);
+        let commitment_y = y.mul_vartime(&self.s) - b.mul_vartime(&self.e);
+
+        dleq_challenge(y, a, b, &commitment_g, &commitment_y).ct_eq(&self.e)
+    }
+}
+
+fn dleq_challenge(
+    y: &S256Point,
+    a: &S256Point,
+    b: &S256Point,
+    commitment_g: &S256Point,
+    commitment_y: &S256Point,
+) -> Scalar {
+    // The points come from untrusted proofs, so infinity must hash rather
+    // than panic
+    let data = [
+        y.sec_or_zero(),
+        a.sec_or_zero(),
+        b.sec_or_zero(),
+        commitment_g.sec_or_zero(),
+        commitment_y.sec_or_zero(),
+    ]
+    .concat();
+
+    Scalar::new(U256::from_be_bytes(&tagged_hash("DLEQ/challenge", &data)))
+}
+
+// Deterministic in the secret and context, with aux_rand mixed in so a fault
+// during signing doesn't leak the key through a reused nonce.
+fn hedged_nonce(tag: &str, secret: &Scalar, context: &[&[u8]], aux_rand: &[u8; 32]) -> Scalar {
+    let capacity = 64 + context.iter().map(|part| part.len()).sum::<usize>();
+    let mut data = Vec::with_capacity(capacity);
+    data.extend_from_slice(&secret.to_be_bytes());
+    data.extend_from_slice(aux_rand);
+    for part in context {
+        data.extend_from_slice(part);
+    }
+
+    let mut hash = tagged_hash(tag, &data);
+    data.zeroize();
+    let k = Scalar::new(U256::from_be_bytes(&hash));
+    hash.zeroize();
+    assert!(!k.is_zero(), "nonce is zero with negligible probability");
+
+    k
+}
+
+fn sign_of(point: &S256Point) -> Scalar {
+    if has_even_y(point) {
+        Scalar::ONE
+    } else {
+        -Scalar::ONE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DleqProof, EcdsaAdaptorSignature, SchnorrAdaptorSignature};
+    use crate::ecc::private_key::PrivateKey;
+    use crate::ecc::s256_point::S256Point;
+    use crate::ecc::scalar::Scalar;
+    use crate::ecc::schnorr::verify;
+    use crate::ecc::x_only_public_key::XOnlyPublicKey;
+
+    #[test]
+    fn schnorr_adaptor_round_trip() {
+        let key = PrivateKey::new(Scalar::from_u64(0x1234_5678)).unwrap();
+        let public_key = XOnlyPublicKey::from_point(&key.public_key()).unwrap().0;
+        let msg = b"atomic swap";
+
+        // Try a few secrets so both parities of R are exercised
+        for t in [3u64, 4, 5, 6] {
+            let secret = Scalar::from_u64(t);
+            let adaptor = S256Point::generator() * secret;
+
+            let adaptor_sig =
+                SchnorrAdaptorSignature::encrypt(&key, msg, &adaptor, &[7u8; 32]).unwrap();
+            assert!(adaptor_sig.verify(&public_key, msg, &adaptor));
+            assert!(!adaptor_sig.verify(&public_key, b"other", &adaptor));
+
+            let sig = adaptor_sig.decrypt(secret);
+            assert!(verify(&public_key, msg, &sig));
+            assert_eq!(adaptor_sig.recover(&sig, &adaptor).unwrap(), secret);
+
+            // The pre-signature by itself isn't a valid signature
+            assert!(!verify(
+                &public_key,
+                msg,
+                &adaptor_sig.decrypt(Scalar::ZERO)
+            ));
+        }
+    }
+
+    #[test]
+    fn ecdsa_adaptor_round_trip() {
+        let key = PrivateKey::new(Scalar::from_u64(0xabcdef)).unwrap();
+        let z = Scalar::from_u64(0x5151);
+        let secret = Scalar::from_u64(42);
+        let adaptor = S256Point::generator() * secret;
+
+        let adaptor_sig = EcdsaAdaptorSignature::encrypt(&key, z, &adaptor, &[1u8; 32]).unwrap();
+        assert!(adaptor_sig.verify(&key.public_key(), z, &adaptor));
+        assert!(!adaptor_sig.verify(&key.public_key(), z + Scalar::ONE, &adaptor));
+        assert!(!adaptor_sig.verify(&key.public_key(), z, &S256Point::generator()));
+
+        let sig = adaptor_sig.decrypt(secret);
+        assert!(key.public_key().verify(z, &sig));
+        assert_eq!(adaptor_sig.recover(&sig, &adaptor).unwrap(), secret);
+
+        let other = PrivateKey::new(Scalar::from_u64(99)).unwrap().sign(z);
+        assert!(adaptor_sig.recover(&other, &adaptor).is_err());
+    }
+
+    #[test]
+    fn dleq() {
+        let k = Scalar::from_u64(1000);
+        let y = S256Point::generator() * 17u64;
+        let a = S256Point::generator() * k;
+        let b = y * k;
+
+        let proof = DleqProof::prove(k, &y, &a, &b, &[0u8; 32]);
+        assert!(proof.verify(&y, &a, &b));
+        assert!(!proof.verify(&y, &a, &(b + y)));
+    }
+}
@@ -0,0 +1,244 @@
+use std::fmt;
+
+use crate::ecc::private_key::PrivateKey;
+use crate::ecc::s256_point::S256Point;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::schnorr::{self, challenge, has_even_y, SchnorrSignature};
+use crate::ecc::uint::U256;
+use crate::ecc::x_only_public_key::{Parity, XOnlyPublicKey};
+use crate::ecc::zeroize::Zeroize;
+use crate::error::Error;
+use crate::hash::tagged_hash;
+
+// Anti-exfiltration BIP340 signing, for a signer (e.g. a hardware wallet)
+// that the host doesn't trust to pick honest nonces:
+//
+// 1. The host picks random host_data and sends host_commitment(host_data).
+// 2. The signer derives k0 from its key, the message and that commitment,
+//    and returns R0 = k0*G.
+// 3. The host reveals host_data.
+// 4. The signer signs with k = k0 + t, t = H(R0 || host_data), so R = R0 + tG.
+// 5. The host checks with verify that R really is R0 + tG.
+//
+// The signer committed to R0 before seeing host_data, so it can't grind R to
+// leak the key, and the host learns nothing about k0 from t.
+
+// Signer's nonce for one anti-exfil signature. Not Clone, and consumed by sign,
+// which also checks it's signing with the key the nonce was made for.
+pub struct AntiExfilNonce {
+    k0: Scalar,
+    commitment: S256Point,
+    host_commitment: [u8; 32],
+    public_key: XOnlyPublicKey,
+}
+
+impl AntiExfilNonce {
+    pub fn commitment(&self) -> S256Point {
+        self.commitment
+    }
+}
+
+impl Drop for AntiExfilNonce {
+    fn drop(&mut self) {
+        self.k0.zeroize();
+    }
+}
+
+impl fmt::Debug for AntiExfilNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AntiExfilNonce")
+            .field("commitment", &self.commitment)
+            .finish_non_exhaustive()
+    }
+}
+
+pub fn host_commitment(host_data: &[u8; 32]) -> [u8; 32] {
+    tagged_hash("AntiExfil/host", host_data)
+}
+
+// Step 2. k0 depends on the host commitment, so signing the same message
+// again with different host data never reuses k0 with a different tweak.
+pub fn signer_commit(key: &PrivateKey, msg: &[u8], host_commitment: &[u8; 32]) -> AntiExfilNonce {
+    let mut secret = key.secret().to_be_bytes();
+    let mut data = Vec::with_capacity(32 + 33 + 32 + msg.len());
+    data.extend_from_slice(&secret);
+    data.extend_from_slice(
+        &key.public_key()
+            .sec_compressed()
+            .expect("private keys are non-zero"),
+    );
+    data.extend_from_slice(host_commitment);
+    data.extend_from_slice(msg);
+    secret.zeroize();
+
+    let mut hash = tagged_hash("AntiExfil/nonce", &data);
+    data.zeroize();
+    let k0 = Scalar::new(U256::from_be_bytes(&hash));
+    hash.zeroize();
+    assert!(!k0.is_zero(), "nonce is zero with negligible probability");
+
+    AntiExfilNonce {
+        k0,
+        commitment: S256Point::mul_generator(&k0),
+        host_commitment: *host_commitment,
+        public_key: XOnlyPublicKey::from_point(&key.public_key())
+            .expect("private keys are non-zero")
+            .0,
+    }
+}
+
+// Step 4. Fails if host_data doesn't open the commitment the nonce was made for.
+pub fn sign(
+    key: &PrivateKey,
+    msg: &[u8],
+    nonce: AntiExfilNonce,
+    host_data: &[u8; 32],
+) -> Result<SchnorrSignature, Error> {
+    if host_commitment(host_data) != nonce.host_commitment {
+        return Err(Error::InvalidInput(
+            "Host data doesn't match the host commitment".to_string(),
+        ));
+    }
+    let tweak = tweak(&nonce.commitment, host_data)?;
+
+    let (public_key, parity) =
+        XOnlyPublicKey::from_point(&key.public_key()).expect("private keys are non-zero");
+    if public_key != nonce.public_key {
+        return Err(Error::InvalidInput(
+            "Nonce was generated for a different key".to_string(),
+        ));
+    }
+    let mut d = match parity {
+        Parity::Even => key.secret(),
+        Parity::Odd => -key.secret(),
+    };
+
+    let mut k = nonce.k0 + tweak;
+    let big_r = S256Point::mul_generator(&k);
+    let r = match big_r.x() {
+        Some(r) => r,
+        None => {
+            k.zeroize();
+            d.zeroize();
+            return Err(Error::PointAtInfinity);
+        }
+    };
+    if !has_even_y(&big_r) {
+        k = -k;
+    }
+
+    let r_bytes = r.to_be_bytes();
+    let e = challenge(&r_bytes, &public_key.serialize(), msg);
+    let s = k + e * d;
+    k.zeroize();
+    d.zeroize();
+
+    Ok(SchnorrSignature { r, s })
+}
+
+// Step 5. A valid BIP340 signature whose R is the committed R0 plus the tweak.
+pub fn verify(
+    public_key: &XOnlyPublicKey,
+    msg: &[u8],
+    sig: &SchnorrSignature,
+    commitment: &S256Point,
+    host_data: &[u8; 32],
+) -> bool {
+    let tweak = match tweak(commitment, host_data) {
+        Ok(tweak) => tweak,
+        Err(_) => return false,
+    };
+    let expected = *commitment + S256Point::mul_generator(&tweak);
+
+    match expected.x() {
+        Some(x) => x == sig.r && schnorr::verify(public_key, msg, sig),
+        None => false,
+    }
+}
+
+fn tweak(commitment: &S256Point, host_data: &[u8; 32]) -> Result<Scalar, Error> {
+    let hash = tagged_hash(
+        "AntiExfil/tweak",
+        &[&commitment.sec_compressed()?[..], host_data].concat(),
+    );
+    Ok(Scalar::new(U256::from_be_bytes(&hash)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{host_commitment, sign, signer_commit, verify};
+    use crate::ecc::private_key::PrivateKey;
+    use crate::ecc::scalar::Scalar;
+    use crate::ecc::schnorr;
+    use crate::ecc::x_only_public_key::XOnlyPublicKey;
+
+    fn x_only(key: &PrivateKey) -> XOnlyPublicKey {
+        XOnlyPublicKey::from_point(&key.public_key()).unwrap().0
+    }
+
+    #[test]
+    fn round_trip() {
+        // 6G has an odd y and 7G an even one
+        for secret in [6u64, 7] {
+            let key = PrivateKey::new(Scalar::from_u64(secret)).unwrap();
+            let msg = b"anti-exfil";
+            let host_data = [0x42; 32];
+
+            let nonce = signer_commit(&key, msg, &host_commitment(&host_data));
+            let commitment = nonce.commitment();
+            let sig = sign(&key, msg, nonce, &host_data).unwrap();
+
+            assert!(schnorr::verify(&x_only(&key), msg, &sig));
+            assert!(verify(&x_only(&key), msg, &sig, &commitment, &host_data));
+        }
+    }
+
+    #[test]
+    fn rejects_a_different_nonce() {
+        let key = PrivateKey::new(Scalar::from_u64(0xfeed)).unwrap();
+        let msg = b"anti-exfil";
+        let host_data = [0x42; 32];
+        let commitment = host_commitment(&host_data);
+
+        let nonce = signer_commit(&key, msg, &commitment);
+        let committed = nonce.commitment();
+        drop(nonce);
+
+        // A signer that ignores its commitment and signs with a fresh nonce
+        // still produces a valid signature, but not one the host accepts
+        let sig = schnorr::sign(&key, msg, &[0u8; 32]);
+        assert!(schnorr::verify(&x_only(&key), msg, &sig));
+        assert!(!verify(&x_only(&key), msg, &sig, &committed, &host_data));
+
+        let nonce = signer_commit(&key, msg, &commitment);
+        let sig = sign(&key, msg, nonce, &host_data).unwrap();
+        assert!(!verify(&x_only(&key), msg, &sig, &committed, &[0x43; 32]));
+    }
+
+    #[test]
+    fn host_data_must_match() {
+        let key = PrivateKey::new(Scalar::from_u64(0xfeed)).unwrap();
+        let nonce = signer_commit(&key, b"msg", &host_commitment(&[1; 32]));
+
+        assert!(sign(&key, b"msg", nonce, &[2; 32]).is_err());
+    }
+
+    #[test]
+    fn key_must_match() {
+        let key = PrivateKey::new(Scalar::from_u64(0xfeed)).unwrap();
+        let other = PrivateKey::new(Scalar::from_u64(0xbeef)).unwrap();
+        let host_data = [1; 32];
+
+        let nonce = signer_commit(&key, b"msg", &host_commitment(&host_data));
+        assert!(sign(&other, b"msg", nonce, &host_data).is_err());
+    }
+
+    #[test]
+    fn nonce_depends_on_host_commitment() {
+        let key = PrivateKey::new(Scalar::from_u64(0xfeed)).unwrap();
+
+        let a = signer_commit(&key, b"msg", &host_commitment(&[1; 32]));
+        let b = signer_commit(&key, b"msg", &host_commitment(&[2; 32]));
+        assert_ne!(a.commitment(), b.commitment());
+    }
+}
@@ -0,0 +1,211 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::ecc::field_element::FieldElement;
+use crate::ecc::uint::U256;
+
+// Element of a small field whose prime is part of the type, like the F_13
+// and F_223 fields from the book. Mixing fields is a compile error, so the
+// operators return Self. P has to be prime for Div and invert to be correct;
+// only P > 1 is checked.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct ConstFieldElement<const P: u64> {
+    num: u64,
+}
+
+impl<const P: u64> ConstFieldElement<P> {
+    const VALID_PRIME: () = assert!(P > 1, "field prime must be greater than one");
+
+    pub const ZERO: Self = Self { num: 0 };
+    pub const ONE: Self = Self { num: 1 };
+
+    pub fn new(num: u64) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::VALID_PRIME;
+
+        Self { num: num % P }
+    }
+
+    // Negative numbers are reduced the same way Python's % does
+    pub fn from_i64(num: i64) -> Self {
+        Self::new((num as i128).rem_euclid(P as i128) as u64)
+    }
+
+    pub fn num(&self) -> u64 {
+        self.num
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    // Exponents are reduced mod P - 1, so negative ones are inverses
+    pub fn pow(&self, exponent: i64) -> Self {
+        let order = P as i128 - 1;
+        let mut n = (exponent as i128).rem_euclid(order) as u64;
+
+        let mut result = Self::new(1);
+        let mut base = *self;
+        while n > 0 {
+            if n & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            n >>= 1;
+        }
+
+        result
+    }
+
+    // Fermat's little theorem, a^(P-2)
+    pub fn invert(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+
+        Some(self.pow(-1))
+    }
+}
+
+impl<const P: u64> Add for ConstFieldElement<P> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(((self.num as u128 + other.num as u128) % P as u128) as u64)
+    }
+}
+
+impl<const P: u64> Sub for ConstFieldElement<P> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl<const P: u64> Mul for ConstFieldElement<P> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new((self.num as u128 * other.num as u128 % P as u128) as u64)
+    }
+}
+
+// Panics on division by zero, like the integer types
+impl<const P: u64> Div for ConstFieldElement<P> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        self * other.invert().expect("division by zero")
+    }
+}
+
+impl<const P: u64> Neg for ConstFieldElement<P> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(P - self.num)
+    }
+}
+
+impl<const P: u64> AddAssign for ConstFieldElement<P> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<const P: u64> SubAssign for ConstFieldElement<P> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<const P: u64> MulAssign for ConstFieldElement<P> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl<const P: u64> DivAssign for ConstFieldElement<P> {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+// Into the runtime-prime version, e.g. to build a Point
+impl<const P: u64> From<ConstFieldElement<P>> for FieldElement {
+    fn from(element: ConstFieldElement<P>) -> Self {
+        FieldElement::new(U256::from(element.num), U256::from(P)).expect("P is non-zero")
+    }
+}
+
+impl<const P: u64> fmt::Display for ConstFieldElement<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.num)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConstFieldElement;
+    use crate::ecc::field_element::FieldElement;
+    use crate::ecc::point::Point;
+
+    type F13 = ConstFieldElement<13>;
+    type F57 = ConstFieldElement<57>;
+    type F223 = ConstFieldElement<223>;
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(F13::new(9) + F13::new(10), F13::new(6));
+        assert_eq!(F13::from_i64(-27) + F13::from_i64(-42), F13::new(9));
+        assert_eq!(F57::new(52) - F57::new(30) - F57::new(38), F57::new(41));
+        assert_eq!(F13::new(3) * F13::new(12), F13::new(10));
+        assert_eq!(F13::new(3).pow(3), F13::new(1));
+        assert_eq!(F13::new(7).pow(-3), F13::new(8));
+        assert_eq!(F13::new(3) / F13::new(24), F13::new(5));
+        assert_eq!(-F13::ZERO, F13::ZERO);
+
+        let mut a = F13::new(5);
+        a += F13::new(9);
+        a *= F13::new(2);
+        a -= F13::ONE;
+        a /= F13::new(3);
+        assert_eq!(a, F13::new(9));
+    }
+
+    #[test]
+    fn inverse() {
+        for n in 1..223 {
+            let a = F223::new(n);
+            assert_eq!(a * a.invert().unwrap(), F223::ONE);
+        }
+
+        assert_eq!(F223::ZERO.invert(), None);
+    }
+
+    #[test]
+    fn large_prime() {
+        // 2^64 - 59, the largest 64-bit prime
+        type F = ConstFieldElement<0xffff_ffff_ffff_ffc5>;
+
+        let a = F::new(u64::MAX);
+        assert_eq!(a, F::new(58));
+        assert_eq!(F::from_i64(-1) * F::from_i64(-1), F::ONE);
+        assert_eq!(a / a, F::ONE);
+    }
+
+    #[test]
+    fn into_runtime_field() {
+        let (a, b) = (F223::new(0), F223::new(7));
+        let (x, y) = (F223::new(192), F223::new(105));
+
+        let point = Point::new(Some(x.into()), Some(y.into()), a.into(), b.into());
+        assert!(point.is_ok());
+        assert_eq!(
+            FieldElement::from(F223::from_i64(-1)),
+            FieldElement::from_i64(-1, 223).unwrap()
+        );
+    }
+}
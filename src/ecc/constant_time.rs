@@ -0,0 +1,109 @@
+use std::hint::black_box;
+
+use crate::ecc::field_element::FieldElement;
+use crate::ecc::s256_field::S256Field;
+use crate::ecc::s256_point::S256Point;
+use crate::ecc::uint::U256;
+
+// Equality that looks at every byte or limb no matter where the first
+// difference is, for values derived from secrets. == may return early.
+pub trait ConstantTimeEq {
+    fn ct_eq(&self, other: &Self) -> bool;
+}
+
+impl<const N: usize> ConstantTimeEq for [u8; N] {
+    fn ct_eq(&self, other: &Self) -> bool {
+        let diff = self
+            .iter()
+            .zip(other)
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+
+        black_box(diff) == 0
+    }
+}
+
+impl ConstantTimeEq for U256 {
+    fn ct_eq(&self, other: &Self) -> bool {
+        let diff = self
+            .0
+            .iter()
+            .zip(&other.0)
+            .fold(0u64, |acc, (a, b)| acc | (a ^ b));
+
+        black_box(diff) == 0
+    }
+}
+
+// Elements of different fields are never equal
+impl ConstantTimeEq for FieldElement {
+    fn ct_eq(&self, other: &Self) -> bool {
+        self.num.ct_eq(&other.num) & self.prime.ct_eq(&other.prime)
+    }
+}
+
+impl ConstantTimeEq for S256Field {
+    fn ct_eq(&self, other: &Self) -> bool {
+        self.num().ct_eq(&other.num())
+    }
+}
+
+// Infinity is compared as (0, 0), which isn't on the curve, together with a
+// flag so it can't collide with an affine point
+impl ConstantTimeEq for S256Point {
+    fn ct_eq(&self, other: &Self) -> bool {
+        let coordinates = |point: &S256Point| {
+            (
+                point.x().unwrap_or(S256Field::ZERO),
+                point.y().unwrap_or(S256Field::ZERO),
+            )
+        };
+        let (x1, y1) = coordinates(self);
+        let (x2, y2) = coordinates(other);
+
+        x1.ct_eq(&x2) & y1.ct_eq(&y2) & (self.is_infinity() == other.is_infinity())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConstantTimeEq;
+    use crate::ecc::field_element::FieldElement;
+    use crate::ecc::s256_field::S256Field;
+    use crate::ecc::s256_point::S256Point;
+    use crate::ecc::scalar::Scalar;
+    use crate::ecc::uint::U256;
+
+    #[test]
+    fn matches_eq() {
+        let mut a = [7u8; 32];
+        let b = a;
+        assert!(a.ct_eq(&b));
+        a[31] ^= 1;
+        assert!(!a.ct_eq(&b));
+
+        assert!(Scalar::from(5u64).ct_eq(&Scalar::from(5u64)));
+        assert!(!Scalar::from(5u64).ct_eq(&-Scalar::from(5u64)));
+
+        assert!(S256Field::from_u64(9).ct_eq(&S256Field::from_u64(9)));
+        assert!(!S256Field::from_u64(9).ct_eq(&S256Field::from_u64(10)));
+    }
+
+    #[test]
+    fn different_primes() {
+        let a = FieldElement::new(U256::from(3u64), U256::from(7u64)).unwrap();
+        let b = FieldElement::new(U256::from(3u64), U256::from(11u64)).unwrap();
+
+        assert!(a.ct_eq(&a));
+        assert!(!a.ct_eq(&b));
+    }
+
+    #[test]
+    fn points() {
+        let g = S256Point::generator();
+
+        assert!(g.ct_eq(&g));
+        assert!(!g.ct_eq(&-g));
+        assert!(!g.ct_eq(&S256Point::infinity()));
+        assert!(S256Point::infinity().ct_eq(&S256Point::infinity()));
+    }
+}
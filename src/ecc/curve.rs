@@ -0,0 +1,257 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::ecc::field_element::FieldElement;
+use crate::ecc::point::{Coordinates, Point};
+use crate::ecc::secp256k1;
+use crate::ecc::uint::U256;
+use crate::error::Error;
+
+// Parameters of a short Weierstrass curve y^2 = x^3 + ax + b over F_prime,
+// with a generator and its order. Implemented by zero-sized marker types so a
+// CurvePoint only has to store its coordinates.
+pub trait Curve: fmt::Debug + Clone + Copy + PartialEq + Eq {
+    const PRIME: U256;
+    const A: U256;
+    const B: U256;
+    const ORDER: U256;
+    const GENERATOR: (U256, U256);
+
+    fn a() -> FieldElement {
+        FieldElement {
+            num: Self::A,
+            prime: Self::PRIME,
+        }
+    }
+
+    fn b() -> FieldElement {
+        FieldElement {
+            num: Self::B,
+            prime: Self::PRIME,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Secp256k1;
+
+impl Curve for Secp256k1 {
+    const PRIME: U256 = secp256k1::P;
+    const A: U256 = U256::ZERO;
+    const B: U256 = U256::from_u64(7);
+    const ORDER: U256 = secp256k1::N;
+    const GENERATOR: (U256, U256) = (secp256k1::GX, secp256k1::GY);
+}
+
+// y^2 = x^3 + 7 over F_223 from chapter 3 of the book, with (47, 71) as the
+// generator of its order 21 subgroup. 21 isn't prime, so it's only fit for
+// the book's exercises.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Curve223;
+
+impl Curve for Curve223 {
+    const PRIME: U256 = U256::from_u64(223);
+    const A: U256 = U256::ZERO;
+    const B: U256 = U256::from_u64(7);
+    const ORDER: U256 = U256::from_u64(21);
+    const GENERATOR: (U256, U256) = (U256::from_u64(47), U256::from_u64(71));
+}
+
+// Point on the curve C. Both operands of every operation are on the same
+// curve by type, so the operators return Self instead of a Result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurvePoint<C: Curve> {
+    coordinates: Coordinates,
+    curve: PhantomData<C>,
+}
+
+impl<C: Curve> CurvePoint<C> {
+    pub fn new(x: U256, y: U256) -> Result<Self, Error> {
+        let x = FieldElement::new(x, C::PRIME)?;
+        let y = FieldElement::new(y, C::PRIME)?;
+
+        Point::affine(x, y, C::a(), C::b()).map(Self::from_point_unchecked)
+    }
+
+    pub fn infinity() -> Self {
+        Self::from_point_unchecked(Point::infinity(C::a(), C::b()))
+    }
+
+    pub fn generator() -> Self {
+        let (x, y) = C::GENERATOR;
+        Self::new(x, y).expect("generator is on the curve")
+    }
+
+    pub fn coordinates(&self) -> Coordinates {
+        self.coordinates
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.coordinates == Coordinates::Infinity
+    }
+
+    pub fn x(&self) -> Option<FieldElement> {
+        self.to_point().x()
+    }
+
+    pub fn y(&self) -> Option<FieldElement> {
+        self.to_point().y()
+    }
+
+    pub fn to_point(&self) -> Point {
+        match self.coordinates {
+            Coordinates::Affine { x, y } => Point::new_unchecked(x, y, C::a(), C::b()),
+            Coordinates::Infinity => Point::infinity(C::a(), C::b()),
+        }
+    }
+
+    fn from_point_unchecked(point: Point) -> Self {
+        Self {
+            coordinates: point.coordinates(),
+            curve: PhantomData,
+        }
+    }
+}
+
+impl<C: Curve> TryFrom<Point> for CurvePoint<C> {
+    type Error = Error;
+
+    fn try_from(point: Point) -> Result<Self, Self::Error> {
+        if point.a() != C::a() || point.b() != C::b() {
+            return Err(Error::CurveMismatch);
+        }
+
+        Ok(Self::from_point_unchecked(point))
+    }
+}
+
+impl<C: Curve> Add for CurvePoint<C> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let sum = (self.to_point() + other.to_point()).expect("points are on the same curve");
+        Self::from_point_unchecked(sum)
+    }
+}
+
+impl<C: Curve> Neg for CurvePoint<C> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from_point_unchecked(-self.to_point())
+    }
+}
+
+impl<C: Curve> Sub for CurvePoint<C> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+// The coefficient isn't reduced mod ORDER: that's only the order of the
+// generator's subgroup, and on curves with a cofactor other points can have
+// a different order
+impl<C: Curve> Mul<U256> for CurvePoint<C> {
+    type Output = Self;
+
+    fn mul(self, coef: U256) -> Self {
+        let product = (self.to_point() * coef).expect("points are on the same curve");
+        Self::from_point_unchecked(product)
+    }
+}
+
+impl<C: Curve> fmt::Display for CurvePoint<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_point())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Curve, Curve223, CurvePoint, Secp256k1};
+    use crate::ecc::point::Point;
+    use crate::ecc::s256_point::S256Point;
+    use crate::ecc::scalar::Scalar;
+    use crate::ecc::secp256k1::G;
+    use crate::ecc::uint::U256;
+    use crate::error::Error;
+
+    // y^2 = x^3 + 2x + 3 over F_97, where (3, 6) generates a subgroup of order 5
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Toy;
+
+    impl Curve for Toy {
+        const PRIME: U256 = U256::from_u64(97);
+        const A: U256 = U256::from_u64(2);
+        const B: U256 = U256::from_u64(3);
+        const ORDER: U256 = U256::from_u64(5);
+        const GENERATOR: (U256, U256) = (U256::from_u64(3), U256::from_u64(6));
+    }
+
+    #[test]
+    fn generator_order() {
+        fn check<C: Curve>() {
+            let g = CurvePoint::<C>::generator();
+            assert!(!g.is_infinity());
+            assert!((g * (C::ORDER - U256::ONE) + g).is_infinity());
+            assert_eq!(g * (C::ORDER + U256::ONE), g);
+        }
+
+        check::<Toy>();
+        check::<Curve223>();
+        check::<Secp256k1>();
+    }
+
+    #[test]
+    fn book_exercises() {
+        type P223 = CurvePoint<Curve223>;
+        let u = U256::from_u64;
+
+        let p1 = P223::new(u(170), u(142)).unwrap();
+        let p2 = P223::new(u(60), u(139)).unwrap();
+        assert_eq!(p1 + p2, P223::new(u(220), u(181)).unwrap());
+
+        let p = P223::new(u(47), u(71)).unwrap();
+        assert_eq!(p * u(2), P223::new(u(36), u(111)).unwrap());
+        assert_eq!(p - p, P223::infinity());
+
+        assert_eq!(P223::new(u(200), u(119)), Err(Error::NotOnCurve));
+    }
+
+    #[test]
+    fn points_outside_generator_subgroup() {
+        type P223 = CurvePoint<Curve223>;
+        let u = U256::from_u64;
+
+        // 6^3 + 7 = 223, so (6, 0) is on the curve with order 2
+        let p = P223::new(u(6), u(0)).unwrap();
+        assert_eq!(p * u(21), p);
+        assert!((p * u(2)).is_infinity());
+    }
+
+    #[test]
+    fn matches_secp256k1() {
+        let g = CurvePoint::<Secp256k1>::generator();
+        assert_eq!(g.to_point(), *G);
+
+        let k = U256::from_u64(0xdeadbeef);
+        let expected = S256Point::generator() * Scalar::new(k);
+        let (x, y) = (expected.x().unwrap(), expected.y().unwrap());
+        assert_eq!(g * k, CurvePoint::new(x.num(), y.num()).unwrap());
+    }
+
+    #[test]
+    fn conversions() {
+        let p = CurvePoint::<Toy>::generator();
+        let point: Point = p.to_point();
+
+        assert_eq!(CurvePoint::<Toy>::try_from(point), Ok(p));
+        assert_eq!(
+            CurvePoint::<Curve223>::try_from(point),
+            Err(Error::CurveMismatch)
+        );
+    }
+}
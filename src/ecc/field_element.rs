@@ -2,103 +2,278 @@ use std::fmt;
 
 use std::ops::{Add, Div, Mul, Sub};
 
-// use crate::ecc::point::Point;
+use crate::ecc::montgomery::Montgomery;
+use crate::ecc::uint::U256;
+use crate::error::Error;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct FieldElement {
-    pub num: i32,
-    pub prime: i32,
+    pub num: U256,
+    pub prime: U256,
 }
 
 impl FieldElement {
-    pub fn new(num: i32, prime: i32) -> Result<Self, String> {
-        let num = num % prime;
+    pub fn new(num: U256, prime: U256) -> Result<Self, Error> {
+        if prime.is_zero() {
+            return Err(Error::InvalidModulus);
+        }
+
+        Ok(Self {
+            num: num % prime,
+            prime,
+        })
+    }
 
+    // Unlike new, rejects values that aren't already reduced mod prime, so a
+    // given element has exactly one encoding
+    pub fn from_be_bytes(bytes: &[u8; 32], prime: U256) -> Result<Self, Error> {
+        let num = U256::from_be_bytes(bytes);
         if num >= prime {
-            return Err(format!("Num {} not in field range 0 to {}", num, prime - 1));
+            return Err(Error::InvalidEncoding(
+                "Field element is not below the prime".to_string(),
+            ));
         }
 
-        Ok(Self { num, prime })
+        Self::new(num, prime)
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        self.num.to_be_bytes()
     }
 
-    pub fn field_power(&self, exponent: i32) -> Result<Self, String> {
-        let n = exponent.rem_euclid(self.prime - 1);
+    // Convenience constructor for the small teaching fields, where negative
+    // numbers are reduced into the field the same way Python's % does.
+    pub fn from_i64(num: i64, prime: u64) -> Result<Self, Error> {
+        if prime == 0 {
+            return Err(Error::InvalidModulus);
+        }
+
+        // In i128 so primes above i64::MAX don't wrap
+        let num = (num as i128).rem_euclid(prime as i128) as u64;
+        Self::new(U256::from(num), U256::from(prime))
+    }
+
+    pub fn field_power(&self, exponent: i64) -> Result<Self, Error> {
+        // Mod 1 everything is zero, and there's no order to reduce by
+        if self.prime <= U256::ONE {
+            return Self::new(U256::ZERO, self.prime);
+        }
+
+        let order = self.prime - U256::ONE;
+        let magnitude = U256::from(exponent.unsigned_abs()) % order;
+
+        let n = if exponent < 0 && !magnitude.is_zero() {
+            order - magnitude
+        } else {
+            magnitude
+        };
+
         let num = mod_exp(self.num, n, self.prime);
         Self::new(num, self.prime)
     }
+
+    // 1/a via binary extended Euclid, falling back to Fermat for p = 2
+    pub fn invert(&self) -> Result<Self, Error> {
+        if self.num.is_zero() {
+            return Err(Error::DivisionByZero);
+        }
+
+        let num = match self.num.inv_mod(&self.prime) {
+            Some(inverse) => inverse,
+            None => mod_exp(self.num, self.prime - U256::from(2u64), self.prime),
+        };
+
+        Self::new(num, self.prime)
+    }
+
+    // Legendre symbol (a/p) by Euler's criterion, a^((p-1)/2): 0 for zero,
+    // 1 for a non-zero square and -1 for a non-square. Every element of the
+    // field with p = 2 is a square.
+    pub fn legendre(&self) -> i8 {
+        if self.num.is_zero() {
+            return 0;
+        }
+
+        if self.prime == U256::from(2u64) {
+            return 1;
+        }
+
+        let exponent = (self.prime - U256::ONE) >> 1;
+        if mod_exp(self.num, exponent, self.prime) == U256::ONE {
+            1
+        } else {
+            -1
+        }
+    }
+
+    // Non-zero squares only; is_square also accepts zero
+    pub fn is_quadratic_residue(&self) -> bool {
+        self.legendre() == 1
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.legendre() != -1
+    }
+
+    // Tonelli-Shanks, returns one of the two roots or None for non-squares.
+    // Only a prime modulus is guaranteed a result; for a composite one every
+    // loop is bounded and a candidate root is only returned if it checks out.
+    pub fn sqrt(&self) -> Option<Self> {
+        let p = self.prime;
+        let num = self.num;
+        let root = |r: U256| (r.mul_mod(&r, &p) == num).then_some(Self { num: r, prime: p });
+
+        if !self.is_square() {
+            return None;
+        }
+
+        if self.num.is_zero() || p == U256::from(2u64) {
+            return Some(*self);
+        }
+
+        // p - 1 = q * 2^s with q odd
+        let mut q = p - U256::ONE;
+        let mut s = 0;
+        while !q.is_odd() {
+            q = q >> 1;
+            s += 1;
+        }
+
+        if s == 1 {
+            // p = 3 mod 4
+            return root(mod_exp(self.num, (p + U256::ONE) >> 2, p));
+        }
+
+        let mut z = Self {
+            num: U256::from(2u64),
+            prime: p,
+        };
+        while z.is_square() {
+            z.num = z.num + U256::ONE;
+            if z.num >= p {
+                return None;
+            }
+        }
+
+        let mut m = s;
+        let mut c = mod_exp(z.num, q, p);
+        let mut t = mod_exp(self.num, q, p);
+        let mut r = mod_exp(self.num, (q + U256::ONE) >> 1, p);
+
+        while t != U256::ONE {
+            // Least i with t^(2^i) = 1
+            let mut i = 0;
+            let mut t2i = t;
+            while t2i != U256::ONE {
+                t2i = t2i.mul_mod(&t2i, &p);
+                i += 1;
+                if i >= m {
+                    return None;
+                }
+            }
+
+            let mut b = c;
+            for _ in 0..m - i - 1 {
+                b = b.mul_mod(&b, &p);
+            }
+
+            m = i;
+            c = b.mul_mod(&b, &p);
+            t = t.mul_mod(&c, &p);
+            r = r.mul_mod(&b, &p);
+        }
+
+        root(r)
+    }
 }
 
 impl Add for FieldElement {
-    type Output = Result<Self, String>;
+    type Output = Result<Self, Error>;
 
     fn add(self, other: Self) -> Self::Output {
         if self.prime != other.prime {
-            return Err("Can't add two numbers from different Fields".to_string());
+            return Err(Error::FieldMismatch);
         }
 
-        let mut num = (self.num + other.num) % self.prime;
-
-        if num < 0 {
-            num += self.prime;
-        }
+        let num = self.num.add_mod(&other.num, &self.prime);
 
         FieldElement::new(num, self.prime)
     }
 }
 
 impl Sub for FieldElement {
-    type Output = Result<Self, String>;
+    type Output = Result<Self, Error>;
 
     fn sub(self, other: Self) -> Self::Output {
         if self.prime != other.prime {
-            return Err("Can't add two numbers from different Fields".to_string());
+            return Err(Error::FieldMismatch);
         }
 
-        let mut num = (self.num - other.num) % self.prime;
-
-        if num < 0 {
-            num += self.prime;
-        }
+        let num = self.num.sub_mod(&other.num, &self.prime);
 
         FieldElement::new(num, self.prime)
     }
 }
 
 impl Div for FieldElement {
-    type Output = Result<Self, String>;
+    type Output = Result<Self, Error>;
 
     fn div(self, other: Self) -> Self::Output {
         if self.prime != other.prime {
-            return Err("Can't divide two numbers from different Fields".to_string());
+            return Err(Error::FieldMismatch);
         }
 
-        let mut num = (self.num * (mod_exp(other.num, self.prime - 2, self.prime))) % self.prime;
-
-        if num < 0 {
-            num += self.prime;
-        }
+        let inverse = other.invert()?;
+        let num = self.num.mul_mod(&inverse.num, &self.prime);
 
         FieldElement::new(num, self.prime)
     }
 }
 
 impl Mul for FieldElement {
-    type Output = Result<Self, String>;
+    type Output = Result<Self, Error>;
 
     fn mul(self, other: Self) -> Self::Output {
-        if other.num >= self.prime {
-            return Err("All numbers should belong to same set".to_string());
+        if self.prime != other.prime {
+            return Err(Error::FieldMismatch);
         }
 
-        let mut res = self.num * other.num;
+        let num = self.num.mul_mod(&other.num, &self.prime);
 
-        res %= self.prime;
+        FieldElement::new(num, self.prime)
+    }
+}
 
-        if res < 0 {
-            res += self.prime;
-        }
+// Reference versions, so callers holding borrows don't need to copy first
+impl Add for &FieldElement {
+    type Output = Result<FieldElement, Error>;
+
+    fn add(self, other: Self) -> Self::Output {
+        *self + *other
+    }
+}
+
+impl Sub for &FieldElement {
+    type Output = Result<FieldElement, Error>;
+
+    fn sub(self, other: Self) -> Self::Output {
+        *self - *other
+    }
+}
 
-        FieldElement::new(res, self.prime)
+impl Mul for &FieldElement {
+    type Output = Result<FieldElement, Error>;
+
+    fn mul(self, other: Self) -> Self::Output {
+        *self * *other
+    }
+}
+
+impl Div for &FieldElement {
+    type Output = Result<FieldElement, Error>;
+
+    fn div(self, other: Self) -> Self::Output {
+        *self / *other
     }
 }
 
@@ -108,18 +283,22 @@ impl fmt::Display for FieldElement {
     }
 }
 
-fn mod_exp(mut base: i32, mut exp: i32, modulus: i32) -> i32 {
-    let mut result = 1;
-    base %= modulus; // Reduce base mod p
+fn mod_exp(base: U256, exp: U256, modulus: U256) -> U256 {
+    // Every odd prime can use Montgomery multiplication; only p = 2 can't
+    if let Some(ctx) = Montgomery::cached(modulus) {
+        let power = ctx.pow(&ctx.encode(&base), &exp);
+        return ctx.decode(&power);
+    }
 
-    while exp > 0 {
-        if exp % 2 == 1 {
-            result = (result * base) % modulus;
-        }
+    let mut result = U256::ONE % modulus;
+    let base = base % modulus; // Reduce base mod p
 
-        base = (base * base) % modulus;
+    for i in (0..exp.bits()).rev() {
+        result = result.mul_mod(&result, &modulus);
 
-        exp /= 2;
+        if exp.bit(i) {
+            result = result.mul_mod(&base, &modulus);
+        }
     }
 
     result
@@ -129,11 +308,18 @@ fn mod_exp(mut base: i32, mut exp: i32, modulus: i32) -> i32 {
 mod tests {
     use super::FieldElement;
     use crate::ecc::field_element::mod_exp;
+    use crate::ecc::uint::U256;
+    use crate::error::Error;
+
+    fn pow(base: u64, exp: u64, prime: u64) -> i64 {
+        let n = mod_exp(U256::from(base), U256::from(exp), U256::from(prime));
+        n.0[0] as i64
+    }
 
     #[test]
     fn basic() {
-        let a = FieldElement::new(7, 13);
-        let b = FieldElement::new(6, 13);
+        let a = FieldElement::from_i64(7, 13);
+        let b = FieldElement::from_i64(6, 13);
 
         assert_eq!(a, a);
         assert_ne!(a, b);
@@ -141,83 +327,251 @@ mod tests {
 
     #[test]
     fn field_addition() {
-        let a = FieldElement::new(9, 13).unwrap();
-        let b = FieldElement::new(10, 13).unwrap();
+        let a = FieldElement::from_i64(9, 13).unwrap();
+        let b = FieldElement::from_i64(10, 13).unwrap();
 
         let c = a + b;
 
         assert!(c.is_ok());
-        assert_eq!(c.unwrap().num, 6);
+        assert_eq!(c.unwrap().num, U256::from(6u64));
 
-        let a = FieldElement::new(-27, 13).unwrap();
-        let b = FieldElement::new(-42, 13).unwrap();
+        let a = FieldElement::from_i64(-27, 13).unwrap();
+        let b = FieldElement::from_i64(-42, 13).unwrap();
 
         let c = a + b;
 
         assert!(c.is_ok());
-        assert_eq!(c.unwrap().num, 9);
+        assert_eq!(c.unwrap().num, U256::from(9u64));
     }
 
     #[test]
     fn field_subtraction() {
-        let a = FieldElement::new(52, 57).unwrap();
-        let b = FieldElement::new(30, 57).unwrap();
+        let a = FieldElement::from_i64(52, 57).unwrap();
+        let b = FieldElement::from_i64(30, 57).unwrap();
 
         let c = a - b;
 
         assert!(c.is_ok());
-        assert_eq!(c.clone().unwrap().num, 22);
+        assert_eq!(c.as_ref().unwrap().num, U256::from(22u64));
 
-        let d = FieldElement::new(38, 57).unwrap();
+        let d = FieldElement::from_i64(38, 57).unwrap();
 
         let e = c.unwrap() - d;
 
         assert!(e.is_ok());
-        assert_eq!(e.unwrap().num, 41);
+        assert_eq!(e.unwrap().num, U256::from(41u64));
     }
 
     #[test]
     fn multiplication() {
-        let a = FieldElement::new(95, 97).unwrap();
-        let b = FieldElement::new(45, 97).unwrap();
-        let c = FieldElement::new(31, 97).unwrap();
+        let a = FieldElement::from_i64(95, 97).unwrap();
+        let b = FieldElement::from_i64(45, 97).unwrap();
+        let c = FieldElement::from_i64(31, 97).unwrap();
         let res = (a * b).unwrap() * c;
 
         assert!(res.is_ok());
-        assert_eq!(res.unwrap().num, 23);
+        assert_eq!(res.unwrap().num, U256::from(23u64));
 
-        let a = FieldElement::new(17, 97).unwrap();
-        let b = FieldElement::new(13, 97).unwrap();
-        let c = FieldElement::new(19, 97).unwrap();
-        let d = FieldElement::new(44, 97).unwrap();
+        let a = FieldElement::from_i64(17, 97).unwrap();
+        let b = FieldElement::from_i64(13, 97).unwrap();
+        let c = FieldElement::from_i64(19, 97).unwrap();
+        let d = FieldElement::from_i64(44, 97).unwrap();
         let res = ((a * b).unwrap() * c).unwrap() * d;
 
         assert!(res.is_ok());
-        assert_eq!(res.unwrap().num, 68);
+        assert_eq!(res.unwrap().num, U256::from(68u64));
 
-        let a = FieldElement::new(mod_exp(12, 7, 97), 97).unwrap();
-        let b = FieldElement::new(mod_exp(77, 49, 97), 97).unwrap();
+        let a = FieldElement::from_i64(pow(12, 7, 97), 97).unwrap();
+        let b = FieldElement::from_i64(pow(77, 49, 97), 97).unwrap();
         let res = a * b;
 
         assert!(res.is_ok());
-        assert_eq!(res.unwrap().num, 63);
+        assert_eq!(res.unwrap().num, U256::from(63u64));
     }
 
     #[test]
     fn division() {
-        let a = FieldElement::new(3, 31).unwrap();
-        let b = FieldElement::new(24, 31).unwrap();
+        let a = FieldElement::from_i64(3, 31).unwrap();
+        let b = FieldElement::from_i64(24, 31).unwrap();
+
+        assert_eq!((a / b).unwrap().num, U256::from(4u64));
+
+        let a = FieldElement::from_i64(1, 31).unwrap();
+        let b = FieldElement::from_i64(pow(17, 3, 31), 31).unwrap();
 
-        assert_eq!((a / b).unwrap().num, 4);
+        assert_eq!((a / b).unwrap().num, U256::from(29u64));
 
-        let a = FieldElement::new(1, 31).unwrap();
-        let b = FieldElement::new(mod_exp(17, 3, 31), 31).unwrap();
+        let a = FieldElement::from_i64(11, 31).unwrap();
+        let b = FieldElement::from_i64(pow(4, 4, 31), 31).unwrap();
 
-        assert_eq!((a / b).unwrap().num, 29);
+        assert_eq!((a / b).unwrap().num, U256::from(13u64));
+
+        let zero = FieldElement::from_i64(0, 31).unwrap();
+        assert!((a / zero).is_err());
+    }
+
+    #[test]
+    #[allow(clippy::op_ref)]
+    fn reference_operators() {
+        let a = FieldElement::from_i64(17, 31).unwrap();
+        let b = FieldElement::from_i64(21, 31).unwrap();
+
+        assert_eq!((&a + &b).unwrap(), (a + b).unwrap());
+        assert_eq!((&a - &b).unwrap(), (a - b).unwrap());
+        assert_eq!((&a * &b).unwrap(), (a * b).unwrap());
+        assert_eq!((&a / &b).unwrap(), (a / b).unwrap());
+    }
+
+    #[test]
+    fn invert() {
+        for n in 1..223 {
+            let a = FieldElement::from_i64(n, 223).unwrap();
+            assert_eq!(a.invert().unwrap(), a.field_power(-1).unwrap());
+        }
+
+        let one = FieldElement::from_i64(1, 2).unwrap();
+        assert_eq!(one.invert().unwrap(), one);
+        assert_eq!(
+            FieldElement::from_i64(0, 223).unwrap().invert(),
+            Err(Error::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn typed_errors() {
+        let a = FieldElement::from_i64(3, 13).unwrap();
+        let b = FieldElement::from_i64(3, 17).unwrap();
+
+        assert_eq!(a + b, Err(Error::FieldMismatch));
+        assert_eq!(a * b, Err(Error::FieldMismatch));
+        assert_eq!(
+            FieldElement::new(U256::ONE, U256::ZERO),
+            Err(Error::InvalidModulus)
+        );
+        assert_eq!(FieldElement::from_i64(5, 0), Err(Error::InvalidModulus));
+    }
+
+    #[test]
+    fn degenerate_and_wide_moduli() {
+        let one = FieldElement::from_i64(7, 1).unwrap();
+        assert!(one.num.is_zero());
+        assert_eq!(one.field_power(-3).unwrap(), one);
+
+        // Largest u64 prime, well above i64::MAX
+        let prime = 18446744073709551557u64;
+        let a = FieldElement::from_i64(-1, prime).unwrap();
+        assert_eq!(a.num, U256::from(prime - 1));
+        assert_eq!(
+            FieldElement::from_i64(i64::MIN, prime).unwrap().num,
+            U256::from(prime - (1u64 << 63))
+        );
+    }
+
+    #[test]
+    fn large_prime() {
+        let prime =
+            U256::from_be_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F");
+        let a = FieldElement::new(
+            U256::from_be_hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"),
+            prime,
+        )
+        .unwrap();
+        let b = FieldElement::new(prime - U256::ONE, prime).unwrap();
+
+        assert_eq!(
+            (a + b).unwrap(),
+            (a - FieldElement::new(U256::ONE, prime).unwrap()).unwrap()
+        );
+        assert_eq!(((a / b).unwrap() * b).unwrap(), a);
+        assert_eq!(
+            a.field_power(-1).unwrap(),
+            (FieldElement::new(U256::ONE, prime).unwrap() / a).unwrap()
+        );
+        assert_eq!((b * b).unwrap().num, U256::ONE);
+    }
+
+    #[test]
+    fn bytes() {
+        let prime = U256::from(223u64);
+        let mut bytes = [0u8; 32];
+        bytes[31] = 222;
+
+        let a = FieldElement::from_be_bytes(&bytes, prime).unwrap();
+        assert_eq!(a, FieldElement::from_i64(-1, 223).unwrap());
+        assert_eq!(a.to_be_bytes(), bytes);
+
+        bytes[31] = 223;
+        assert!(FieldElement::from_be_bytes(&bytes, prime).is_err());
+        assert!(FieldElement::from_be_bytes(&[0u8; 32], U256::ZERO).is_err());
+    }
+
+    #[test]
+    fn legendre() {
+        // Squares mod 7 are 1, 2 and 4
+        let symbols: Vec<i8> = (0..7)
+            .map(|n| FieldElement::from_i64(n, 7).unwrap().legendre())
+            .collect();
+        assert_eq!(symbols, vec![0, 1, 1, -1, 1, -1, -1]);
+
+        let zero = FieldElement::from_i64(0, 7).unwrap();
+        assert!(!zero.is_quadratic_residue());
+        assert!(zero.is_square());
+        assert!(FieldElement::from_i64(2, 7).unwrap().is_quadratic_residue());
+        assert!(!FieldElement::from_i64(3, 7).unwrap().is_quadratic_residue());
+        assert_eq!(FieldElement::from_i64(1, 2).unwrap().legendre(), 1);
+
+        // -1 is a non-residue exactly when p = 3 mod 4
+        assert_eq!(FieldElement::from_i64(-1, 223).unwrap().legendre(), -1);
+        assert_eq!(FieldElement::from_i64(-1, 97).unwrap().legendre(), 1);
+    }
+
+    #[test]
+    fn square_roots() {
+        // 223 = 3 mod 4 takes the shortcut, 97 and 113 (= 1 mod 16) run the full loop
+        for prime in [223u64, 97, 113] {
+            let mut squares = 0;
+
+            for n in 0..prime as i64 {
+                let a = FieldElement::from_i64(n, prime).unwrap();
+
+                match a.sqrt() {
+                    Some(root) => {
+                        assert!(a.is_square());
+                        assert_eq!((root * root).unwrap(), a);
+                        squares += 1;
+                    }
+                    None => assert!(!a.is_square()),
+                }
+            }
+
+            assert_eq!(squares, (prime - 1) / 2 + 1);
+        }
+    }
+
+    #[test]
+    fn sqrt_with_composite_modulus() {
+        // None of these are prime; sqrt must still stop, and any root it
+        // returns must square back to the input
+        for modulus in [4i64, 9, 15, 21, 25, 33, 65, 1729] {
+            for num in 0..modulus {
+                let element = FieldElement::from_i64(num, modulus as u64).unwrap();
+                if let Some(root) = element.sqrt() {
+                    assert_eq!((root * root).unwrap(), element);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sqrt_on_teaching_curve() {
+        // (47, 71) is on y^2 = x^3 + 7 over F_223
+        let x = FieldElement::from_i64(47, 223).unwrap();
+        let y = FieldElement::from_i64(71, 223).unwrap();
+        let seven = FieldElement::from_i64(7, 223).unwrap();
 
-        let a = FieldElement::new(11, 31).unwrap();
-        let b = FieldElement::new(mod_exp(4, 4, 31), 31).unwrap();
+        let root = (x.field_power(3).unwrap() + seven).unwrap().sqrt().unwrap();
+        let minus_y = FieldElement::from_i64(-71, 223).unwrap();
 
-        assert_eq!((a / b).unwrap().num, 13);
+        assert!(root == y || root == minus_y);
     }
 }
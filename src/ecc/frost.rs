@@ -0,0 +1,462 @@
+use std::fmt;
+
+use rand_core::{CryptoRng, OsRng, RngCore};
+
+use crate::ecc::constant_time::ConstantTimeEq;
+use crate::ecc::s256_point::S256Point;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::schnorr::{challenge, has_even_y, SchnorrSignature};
+use crate::ecc::uint::U256;
+use crate::ecc::x_only_public_key::{Parity, XOnlyPublicKey};
+use crate::ecc::zeroize::Zeroize;
+use crate::error::Error;
+use crate::hash::tagged_hash;
+
+// A participant's share f(index) of the group secret f(0). Indices start at 1
+// since the share at 0 would be the secret itself.
+pub struct SecretShare {
+    index: u32,
+    value: Scalar,
+}
+
+impl SecretShare {
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn public_share(&self) -> S256Point {
+        S256Point::mul_generator(&self.value)
+    }
+
+    // Feldman check: f(index)G must equal sum(C_j * index^j)
+    pub fn verify(&self, commitments: &[S256Point]) -> bool {
+        self.public_share()
+            .ct_eq(&public_share(commitments, self.index))
+    }
+}
+
+impl Drop for SecretShare {
+    fn drop(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+impl fmt::Debug for SecretShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretShare")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+// Splits a fresh random secret into shares for participants 1..=participants,
+// any threshold of which can sign. Returns the shares and the commitments to
+// the polynomial coefficients; the first commitment is the group key.
+pub fn trusted_dealer(
+    threshold: usize,
+    participants: u32,
+) -> Result<(Vec<SecretShare>, Vec<S256Point>), Error> {
+    trusted_dealer_with(threshold, participants, &mut OsRng)
+}
+
+pub fn trusted_dealer_with<R: RngCore + CryptoRng>(
+    threshold: usize,
+    participants: u32,
+    rng: &mut R,
+) -> Result<(Vec<SecretShare>, Vec<S256Point>), Error> {
+    let mut coefficients: Vec<Scalar> = (0..threshold).map(|_| random_scalar(rng, &[])).collect();
+    let result = split(&coefficients, participants);
+    coefficients.zeroize();
+
+    result
+}
+
+// Same as trusted_dealer with the polynomial given explicitly, coefficients[0]
+// being the group secret.
+pub fn split(
+    coefficients: &[Scalar],
+    participants: u32,
+) -> Result<(Vec<SecretShare>, Vec<S256Point>), Error> {
+    let threshold = coefficients.len();
+    if threshold == 0 || threshold > participants as usize {
+        return Err(Error::InvalidInput(format!(
+            "Threshold must be between 1 and {}, got {}",
+            participants, threshold
+        )));
+    }
+
+    if coefficients[0].is_zero() {
+        return Err(Error::InvalidInput(
+            "Group secret can't be zero".to_string(),
+        ));
+    }
+
+    let shares = (1..=participants)
+        .map(|index| {
+            // Horner's rule, from the highest coefficient down
+            let x = Scalar::from_u64(index as u64);
+            let value = coefficients
+                .iter()
+                .rev()
+                .fold(Scalar::ZERO, |acc, coefficient| acc * x + *coefficient);
+
+            SecretShare { index, value }
+        })
+        .collect();
+
+    let commitments = coefficients.iter().map(S256Point::mul_generator).collect();
+
+    Ok((shares, commitments))
+}
+
+pub fn group_key(commitments: &[S256Point]) -> Result<XOnlyPublicKey, Error> {
+    let first = commitments
+        .first()
+        .ok_or_else(|| Error::InvalidInput("Dealer published no commitments".to_string()))?;
+
+    Ok(XOnlyPublicKey::from_point(first)?.0)
+}
+
+pub fn public_share(commitments: &[S256Point], index: u32) -> S256Point {
+    let x = Scalar::from_u64(index as u64);
+
+    commitments
+        .iter()
+        .rev()
+        .fold(S256Point::infinity(), |acc, commitment| {
+            acc.mul_vartime(&x) + *commitment
+        })
+}
+
+// Hiding and binding nonces for one signing session. Not Clone, and consumed
+// by SigningPackage::sign so they can't be used twice.
+pub struct SigningNonces {
+    index: u32,
+    hiding: Scalar,
+    binding: Scalar,
+}
+
+impl Drop for SigningNonces {
+    fn drop(&mut self) {
+        self.hiding.zeroize();
+        self.binding.zeroize();
+    }
+}
+
+impl fmt::Debug for SigningNonces {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningNonces")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SigningCommitment {
+    pub index: u32,
+    pub hiding: S256Point,
+    pub binding: S256Point,
+}
+
+// Round one. The nonces mix fresh randomness with the share so a broken
+// random source alone doesn't repeat them.
+pub fn commit(share: &SecretShare) -> (SigningNonces, SigningCommitment) {
+    commit_with(share, &mut OsRng)
+}
+
+pub fn commit_with<R: RngCore + CryptoRng>(
+    share: &SecretShare,
+    rng: &mut R,
+) -> (SigningNonces, SigningCommitment) {
+    let mut secret = share.value.to_be_bytes();
+    let hiding = random_scalar(rng, &secret);
+    let binding = random_scalar(rng, &secret);
+    secret.zeroize();
+
+    let nonces = SigningNonces {
+        index: share.index,
+        hiding,
+        binding,
+    };
+    let commitment = SigningCommitment {
+        index: share.index,
+        hiding: S256Point::mul_generator(&hiding),
+        binding: S256Point::mul_generator(&binding),
+    };
+
+    (nonces, commitment)
+}
+
+// Everything derived from the signing set's commitments and the message:
+// each signer's binding factor, the group nonce R and the BIP340 challenge.
+#[derive(Debug, Clone)]
+pub struct SigningPackage {
+    group_key: S256Point,
+    commitments: Vec<SigningCommitment>,
+    binding_factors: Vec<Scalar>,
+    r: S256Point,
+    c: Scalar,
+}
+
+impl SigningPackage {
+    pub fn new(
+        group_key: &S256Point,
+        commitments: &[SigningCommitment],
+        msg: &[u8],
+    ) -> Result<Self, Error> {
+        let mut commitments = commitments.to_vec();
+        commitments.sort_by_key(|commitment| commitment.index);
+
+        if commitments.is_empty() {
+            return Err(Error::InvalidInput("Signing set is empty".to_string()));
+        }
+        if commitments
+            .windows(2)
+            .any(|pair| pair[0].index == pair[1].index)
+        {
+            return Err(Error::InvalidInput(
+                "Signing set has a duplicate participant".to_string(),
+            ));
+        }
+        if commitments.iter().any(|commitment| commitment.index == 0) {
+            return Err(Error::InvalidInput(
+                "Participant index can't be zero".to_string(),
+            ));
+        }
+
+        let q_bytes = XOnlyPublicKey::from_point(group_key)?.0.serialize();
+
+        // Encoding fails for infinity, so malformed commitments are rejected here
+        let mut encoded = Vec::with_capacity(70 * commitments.len());
+        for commitment in &commitments {
+            encoded.extend_from_slice(&commitment.index.to_be_bytes());
+            encoded.extend_from_slice(&commitment.hiding.sec_compressed()?);
+            encoded.extend_from_slice(&commitment.binding.sec_compressed()?);
+        }
+        let prefix = [
+            &q_bytes[..],
+            &tagged_hash("FROST/msg", msg),
+            &tagged_hash("FROST/commitments", &encoded),
+        ]
+        .concat();
+
+        // Binding every nonce to the whole signing set stops a signer from
+        // choosing its nonce after seeing the others
+        let binding_factors: Vec<Scalar> = commitments
+            .iter()
+            .map(|commitment| {
+                let data = [&prefix[..], &commitment.index.to_be_bytes()].concat();
+                Scalar::new(U256::from_be_bytes(&tagged_hash("FROST/binding", &data)))
+            })
+            .collect();
+
+        let r = commitments
+            .iter()
+            .zip(&binding_factors)
+            .fold(S256Point::infinity(), |acc, (commitment, rho)| {
+                acc + commitment.hiding + commitment.binding.mul_vartime(rho)
+            });
+
+        let r_bytes = match r.x() {
+            Some(x) => x.to_be_bytes(),
+            None => return Err(Error::PointAtInfinity),
+        };
+        let c = challenge(&r_bytes, &q_bytes, msg);
+
+        Ok(Self {
+            group_key: *group_key,
+            commitments,
+            binding_factors,
+            r,
+            c,
+        })
+    }
+
+    // Round two: z_i = d_i + rho_i * e_i + lambda_i * s_i * c, with the
+    // nonces and share negated as needed for R and the group key to have even y.
+    pub fn sign(&self, nonces: SigningNonces, share: &SecretShare) -> Result<Scalar, Error> {
+        if nonces.index != share.index {
+            return Err(Error::InvalidInput(
+                "Nonces were generated for a different participant".to_string(),
+            ));
+        }
+
+        let position = self.position(share.index).ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "Participant {} isn't in the signing set",
+                share.index
+            ))
+        })?;
+
+        let rho = self.binding_factors[position];
+        let lambda = self.lagrange_coefficient(share.index);
+
+        Ok(self.r_sign() * (nonces.hiding + rho * nonces.binding)
+            + lambda * self.key_sign() * share.value * self.c)
+    }
+
+    pub fn verify_share(&self, index: u32, share: Scalar, public_share: &S256Point) -> bool {
+        let position = match self.position(index) {
+            Some(position) => position,
+            None => return false,
+        };
+
+        let commitment = &self.commitments[position];
+        let rho = self.binding_factors[position];
+        let lambda = self.lagrange_coefficient(index);
+
+        let expected = (commitment.hiding + commitment.binding.mul_vartime(&rho))
+            .mul_vartime(&self.r_sign())
+            + public_share.mul_vartime(&(lambda * self.key_sign() * self.c));
+
+        S256Point::mul_generator(&share).ct_eq(&expected)
+    }
+
+    pub fn aggregate(&self, shares: &[Scalar]) -> SchnorrSignature {
+        SchnorrSignature {
+            r: self.r.x().expect("R isn't infinity"),
+            s: shares.iter().fold(Scalar::ZERO, |acc, share| acc + *share),
+        }
+    }
+
+    fn position(&self, index: u32) -> Option<usize> {
+        self.commitments
+            .iter()
+            .position(|commitment| commitment.index == index)
+    }
+
+    // Interpolates f(0) from the signing set: prod(x_j / (x_j - x_i)) over j != i
+    fn lagrange_coefficient(&self, index: u32) -> Scalar {
+        let x_i = Scalar::from_u64(index as u64);
+
+        self.commitments
+            .iter()
+            .filter(|commitment| commitment.index != index)
+            .fold(Scalar::ONE, |acc, commitment| {
+                let x_j = Scalar::from_u64(commitment.index as u64);
+                acc * x_j * (x_j - x_i).invert()
+            })
+    }
+
+    fn r_sign(&self) -> Scalar {
+        if has_even_y(&self.r) {
+            Scalar::ONE
+        } else {
+            -Scalar::ONE
+        }
+    }
+
+    fn key_sign(&self) -> Scalar {
+        match XOnlyPublicKey::from_point(&self.group_key) {
+            Ok((_, Parity::Odd)) => -Scalar::ONE,
+            _ => Scalar::ONE,
+        }
+    }
+}
+
+fn random_scalar<R: RngCore + CryptoRng>(rng: &mut R, secret: &[u8]) -> Scalar {
+    let mut data = vec![0u8; 32 + secret.len()];
+    data[32..].copy_from_slice(secret);
+
+    loop {
+        rng.fill_bytes(&mut data[..32]);
+        let mut hash = tagged_hash("FROST/nonce", &data);
+        let k = Scalar::new(U256::from_be_bytes(&hash));
+        hash.zeroize();
+
+        if !k.is_zero() {
+            data.zeroize();
+            return k;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{commit, group_key, public_share, split, trusted_dealer, SigningPackage};
+    use crate::ecc::s256_point::S256Point;
+    use crate::ecc::scalar::Scalar;
+    use crate::ecc::schnorr::verify;
+    use crate::error::Error;
+
+    #[test]
+    fn shares_match_commitments() {
+        let coefficients = [Scalar::from_u64(7), Scalar::from_u64(3)];
+        let (shares, commitments) = split(&coefficients, 3).unwrap();
+
+        // f(x) = 7 + 3x
+        assert_eq!(shares[1].value, Scalar::from_u64(13));
+        for share in &shares {
+            assert!(share.verify(&commitments));
+        }
+
+        let (other_shares, _) = split(&[Scalar::from_u64(8), Scalar::from_u64(3)], 3).unwrap();
+        assert!(!other_shares[0].verify(&commitments));
+
+        assert!(split(&coefficients, 1).is_err());
+        assert!(split(&[Scalar::ZERO], 1).is_err());
+        assert!(trusted_dealer(0, 3).is_err());
+    }
+
+    #[test]
+    fn two_of_three_signing() {
+        let (shares, commitments) = trusted_dealer(2, 3).unwrap();
+        let group = commitments[0];
+        let msg = b"FROST test message";
+
+        for signers in [[0, 1], [0, 2], [1, 2]] {
+            let (nonces, signing_commitments): (Vec<_>, Vec<_>) =
+                signers.iter().map(|i| commit(&shares[*i])).unzip();
+            let package = SigningPackage::new(&group, &signing_commitments, msg).unwrap();
+
+            let signature_shares: Vec<Scalar> = nonces
+                .into_iter()
+                .zip(signers)
+                .map(|(nonces, i)| package.sign(nonces, &shares[i]).unwrap())
+                .collect();
+
+            let sig = package.aggregate(&signature_shares);
+            assert!(verify(&group_key(&commitments).unwrap(), msg, &sig));
+        }
+    }
+
+    #[test]
+    fn share_verification() {
+        let (shares, commitments) = trusted_dealer(2, 3).unwrap();
+        let msg = b"msg";
+
+        let (nonces_a, commitment_a) = commit(&shares[0]);
+        let (nonces_c, commitment_c) = commit(&shares[2]);
+        let package =
+            SigningPackage::new(&commitments[0], &[commitment_c, commitment_a], msg).unwrap();
+
+        let share_a = package.sign(nonces_a, &shares[0]).unwrap();
+        assert!(package.verify_share(1, share_a, &public_share(&commitments, 1)));
+        assert!(!package.verify_share(3, share_a, &public_share(&commitments, 3)));
+
+        // Participant 2 isn't in the signing set
+        assert!(package.sign(nonces_c, &shares[1]).is_err());
+    }
+
+    #[test]
+    fn below_threshold_fails() {
+        let (shares, commitments) = trusted_dealer(2, 3).unwrap();
+        let msg = b"msg";
+
+        let (nonces, commitment) = commit(&shares[0]);
+        let package = SigningPackage::new(&commitments[0], &[commitment], msg).unwrap();
+        let sig = package.aggregate(&[package.sign(nonces, &shares[0]).unwrap()]);
+
+        assert!(!verify(&group_key(&commitments).unwrap(), msg, &sig));
+        assert!(SigningPackage::new(&commitments[0], &[commitment, commitment], msg).is_err());
+    }
+
+    #[test]
+    fn malformed_commitments() {
+        assert!(matches!(group_key(&[]), Err(Error::InvalidInput(_))));
+        assert_eq!(
+            group_key(&[S256Point::infinity()]),
+            Err(Error::PointAtInfinity)
+        );
+    }
+}
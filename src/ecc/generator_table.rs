@@ -0,0 +1,124 @@
+use std::sync::LazyLock;
+
+use crate::ecc::jacobian::JacobianPoint;
+use crate::ecc::s256_field::S256Field;
+use crate::ecc::s256_point::S256Point;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::secp256k1::N;
+use crate::ecc::uint::U256;
+
+const WINDOW_BITS: u32 = 4;
+const WINDOWS: usize = 64;
+const ODD_MULTIPLES: usize = 8;
+
+// Multiples of G used for fixed-base multiplication: for each 4-bit window i
+// the odd multiples 1, 3, .., 15 of 16^i * G, plus 16^64 * G for the final
+// digit. Built once, on first use, as ~32KB of affine coordinates.
+struct GeneratorTable {
+    windows: Vec<[(S256Field, S256Field); ODD_MULTIPLES]>,
+    top: S256Point,
+}
+
+static TABLE: LazyLock<GeneratorTable> = LazyLock::new(|| {
+    let mut windows = Vec::with_capacity(WINDOWS);
+    let mut base = JacobianPoint::from_affine(&S256Point::generator());
+
+    for _ in 0..WINDOWS {
+        let twice = base.double();
+        let mut multiple = base;
+        let mut entries = [(S256Field::ZERO, S256Field::ZERO); ODD_MULTIPLES];
+
+        for entry in entries.iter_mut() {
+            let affine = multiple.to_affine();
+            *entry = (
+                affine.x().expect("odd multiples of G are never infinity"),
+                affine.y().expect("odd multiples of G are never infinity"),
+            );
+            multiple = multiple.add(&twice);
+        }
+
+        windows.push(entries);
+        for _ in 0..WINDOW_BITS {
+            base = base.double();
+        }
+    }
+
+    GeneratorTable {
+        windows,
+        top: base.to_affine(),
+    }
+});
+
+// k*G without any doublings. k is recoded into 64 odd signed digits in
+// [-15, 15] plus a final digit of 1, so every window adds exactly one table
+// entry. The entry is read by scanning the whole row and negated with a
+// masked swap, so neither the memory access pattern nor the operation count
+// depends on k.
+pub(crate) fn mul_generator(k: &Scalar) -> S256Point {
+    // The recoding needs an odd scalar: for even k use N - k, which is odd,
+    // and negate the result
+    let negate = !k.num().is_odd();
+    let mut remaining = if negate { N - k.num() } else { k.num() };
+    let mut result = JacobianPoint::INFINITY;
+
+    for row in &TABLE.windows {
+        // d = (k mod 32) - 16 is odd, and k - d is then divisible by 16 with
+        // an odd quotient. k - d is k with its low five bits cleared plus 16,
+        // so neither the update nor the digit's sign and index need a branch.
+        let low = remaining.0[0] & 0x1f;
+        remaining = (remaining - U256::from(low) + U256::from(16u64)) >> WINDOW_BITS;
+
+        // d < 0 exactly when bit 4 is clear. |d| is low - 16 or 16 - low, and
+        // (|d| - 1) / 2 is the low four bits, inverted for negative d, halved.
+        let negative = (low >> 4) ^ 1;
+        let index = (((low & 0xf) ^ (0xf * negative)) >> 1) as usize;
+        let (mut x, mut y) = row[0];
+        for (j, entry) in row.iter().enumerate().skip(1) {
+            let (mut entry_x, mut entry_y) = *entry;
+            S256Field::conditional_swap(&mut x, &mut entry_x, j == index);
+            S256Field::conditional_swap(&mut y, &mut entry_y, j == index);
+        }
+
+        let mut negated = -y;
+        S256Field::conditional_swap(&mut y, &mut negated, negative == 1);
+
+        result = result.add_affine(&S256Point::from_affine_unchecked(x, y));
+    }
+
+    // Whatever is left after 64 windows is the final digit, always 1
+    debug_assert_eq!(remaining, U256::ONE);
+    let mut result = result.add_affine(&TABLE.top);
+    result.conditional_negate(negate);
+
+    result.to_affine()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mul_generator;
+    use crate::ecc::s256_point::S256Point;
+    use crate::ecc::scalar::Scalar;
+    use crate::ecc::uint::U256;
+
+    #[test]
+    fn matches_double_and_add() {
+        let g = S256Point::generator();
+        let scalars = [
+            Scalar::ONE,
+            Scalar::from_u64(2),
+            Scalar::from_u64(15),
+            Scalar::from_u64(16),
+            Scalar::from_u64(0xdead_beef),
+            -Scalar::ONE,
+            -Scalar::from_u64(2),
+            Scalar::new(U256::from_be_hex(
+                "8000000000000000000000000000000000000000000000000000000000000000",
+            )),
+        ];
+
+        for k in scalars {
+            assert_eq!(mul_generator(&k), g * k);
+        }
+        assert_eq!(mul_generator(&Scalar::ZERO), S256Point::infinity());
+    }
+}
@@ -0,0 +1,238 @@
+use crate::ecc::s256_field::S256Field;
+use crate::ecc::s256_point::S256Point;
+
+// Point on secp256k1 in Jacobian coordinates, (X, Y, Z) standing for the
+// affine point (X/Z^2, Y/Z^3). Adding and doubling need no field inversion,
+// so a whole scalar multiplication only inverts once, when converting back.
+// Z = 0 is the point at infinity.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct JacobianPoint {
+    x: S256Field,
+    y: S256Field,
+    z: S256Field,
+}
+
+impl JacobianPoint {
+    pub(crate) const INFINITY: Self = Self {
+        x: S256Field::ONE,
+        y: S256Field::ONE,
+        z: S256Field::ZERO,
+    };
+
+    pub(crate) fn from_affine(point: &S256Point) -> Self {
+        match (point.x(), point.y()) {
+            (Some(x), Some(y)) => Self {
+                x,
+                y,
+                z: S256Field::ONE,
+            },
+            _ => Self::INFINITY,
+        }
+    }
+
+    pub(crate) fn to_affine(self) -> S256Point {
+        if self.is_infinity() {
+            return S256Point::infinity();
+        }
+
+        let z_inv = self.z.inverse();
+        let z_inv2 = z_inv * z_inv;
+
+        S256Point::from_affine_unchecked(self.x * z_inv2, self.y * z_inv2 * z_inv)
+    }
+
+    pub(crate) fn conditional_swap(a: &mut Self, b: &mut Self, choice: bool) {
+        S256Field::conditional_swap(&mut a.x, &mut b.x, choice);
+        S256Field::conditional_swap(&mut a.y, &mut b.y, choice);
+        S256Field::conditional_swap(&mut a.z, &mut b.z, choice);
+    }
+
+    pub(crate) fn conditional_negate(&mut self, choice: bool) {
+        let mut negated = -self.y;
+        S256Field::conditional_swap(&mut self.y, &mut negated, choice);
+    }
+
+    pub(crate) fn is_infinity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    // dbl-2009-l, which relies on a = 0
+    pub(crate) fn double(&self) -> Self {
+        if self.is_infinity() || self.y.is_zero() {
+            return Self::INFINITY;
+        }
+
+        let a = self.x * self.x;
+        let b = self.y * self.y;
+        let c = b * b;
+        let t = self.x + b;
+        let d = t * t - a - c;
+        let d = d + d;
+        let e = a + a + a;
+        let f = e * e;
+        let c8 = c + c;
+        let c8 = c8 + c8;
+        let c8 = c8 + c8;
+        let yz = self.y * self.z;
+
+        let x3 = f - d - d;
+        let y3 = e * (d - x3) - c8;
+        let z3 = yz + yz;
+
+        Self {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    // add-2007-bl
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        if self.is_infinity() {
+            return *other;
+        }
+        if other.is_infinity() {
+            return *self;
+        }
+
+        let z1z1 = self.z * self.z;
+        let z2z2 = other.z * other.z;
+        let u1 = self.x * z2z2;
+        let u2 = other.x * z1z1;
+        let s1 = self.y * other.z * z2z2;
+        let s2 = other.y * self.z * z1z1;
+
+        if u1 == u2 {
+            // Same x: either the same point or opposite points
+            return if s1 == s2 {
+                self.double()
+            } else {
+                Self::INFINITY
+            };
+        }
+
+        let h = u2 - u1;
+        let i = (h + h) * (h + h);
+        let j = h * i;
+        let r = (s2 - s1) + (s2 - s1);
+        let v = u1 * i;
+
+        let x3 = r * r - j - v - v;
+        let y3 = r * (v - x3) - (s1 * j + s1 * j);
+        let z_sum = self.z + other.z;
+        let z3 = (z_sum * z_sum - z1z1 - z2z2) * h;
+
+        Self {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    // madd-2007-bl: other has Z = 1, which saves several multiplications
+    // over add. This is the common case when adding a fixed affine point.
+    pub(crate) fn add_affine(&self, other: &S256Point) -> Self {
+        let (x2, y2) = match (other.x(), other.y()) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return *self,
+        };
+        if self.is_infinity() {
+            return Self::from_affine(other);
+        }
+
+        let z1z1 = self.z * self.z;
+        let u2 = x2 * z1z1;
+        let s2 = y2 * self.z * z1z1;
+
+        if self.x == u2 {
+            return if self.y == s2 {
+                self.double()
+            } else {
+                Self::INFINITY
+            };
+        }
+
+        let h = u2 - self.x;
+        let hh = h * h;
+        let i = hh + hh + hh + hh;
+        let j = h * i;
+        let r = (s2 - self.y) + (s2 - self.y);
+        let v = self.x * i;
+
+        let x3 = r * r - j - v - v;
+        let y3 = r * (v - x3) - (self.y * j + self.y * j);
+        let z_sum = self.z + h;
+        let z3 = z_sum * z_sum - z1z1 - hh;
+
+        Self {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JacobianPoint;
+    use crate::ecc::point::Point;
+    use crate::ecc::s256_point::S256Point;
+    use crate::ecc::secp256k1::G;
+
+    // Reference results from the generic affine arithmetic on Point
+    fn affine(point: Point) -> S256Point {
+        S256Point::try_from(point).unwrap()
+    }
+
+    #[test]
+    fn matches_affine_arithmetic() {
+        let p = affine((*G * 3u64).unwrap());
+        let q = affine((*G * 5u64).unwrap());
+        let jp = JacobianPoint::from_affine(&p);
+        let jq = JacobianPoint::from_affine(&q);
+
+        assert_eq!(jp.double().to_affine(), affine((*G * 6u64).unwrap()));
+        assert_eq!(jp.add_affine(&q).to_affine(), affine((*G * 8u64).unwrap()));
+        assert_eq!(jp.add(&jq).to_affine(), affine((*G * 8u64).unwrap()));
+
+        // Both operands with Z != 1
+        let sum = jp.double().add(&jq.double());
+        assert_eq!(sum.to_affine(), affine((*G * 16u64).unwrap()));
+
+        // Z != 1 on the Jacobian side
+        assert_eq!(
+            jp.double().add_affine(&q).to_affine(),
+            affine((*G * 11u64).unwrap())
+        );
+    }
+
+    #[test]
+    fn special_cases() {
+        let p = S256Point::generator() * 7u64;
+        let minus_p = -p;
+        let jp = JacobianPoint::from_affine(&p).double().add_affine(&minus_p);
+
+        // jp is P with Z != 1, so adding P hits the doubling branch and adding
+        // -P gives infinity
+        assert_eq!(
+            jp.add_affine(&p).to_affine(),
+            S256Point::generator() * 14u64
+        );
+        assert!(jp.add_affine(&minus_p).is_infinity());
+        assert_eq!(
+            jp.add(&JacobianPoint::from_affine(&p)).to_affine(),
+            S256Point::generator() * 14u64
+        );
+        assert!(jp.add(&JacobianPoint::from_affine(&minus_p)).is_infinity());
+
+        let infinity = JacobianPoint::INFINITY;
+        assert_eq!(infinity.add_affine(&p).to_affine(), p);
+        assert_eq!(infinity.add(&jp).to_affine(), p);
+        assert_eq!(jp.add(&infinity).to_affine(), p);
+        assert!(infinity.double().is_infinity());
+        assert_eq!(
+            JacobianPoint::from_affine(&S256Point::infinity()).to_affine(),
+            S256Point::infinity()
+        );
+    }
+}
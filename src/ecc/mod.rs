@@ -1,2 +1,23 @@
+pub mod adaptor;
+pub mod anti_exfil;
+pub mod const_field;
+pub mod constant_time;
+pub mod curve;
 pub mod field_element;
-pub mod point;
\ No newline at end of file
+pub mod frost;
+pub(crate) mod generator_table;
+pub(crate) mod jacobian;
+pub(crate) mod montgomery;
+pub mod musig2;
+pub mod pedersen;
+pub mod point;
+pub mod private_key;
+pub mod s256_field;
+pub mod s256_point;
+pub mod scalar;
+pub mod schnorr;
+pub mod secp256k1;
+pub mod signature;
+pub mod uint;
+pub mod x_only_public_key;
+pub mod zeroize;
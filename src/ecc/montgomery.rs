@@ -0,0 +1,171 @@
+#![allow(clippy::needless_range_loop)] // limb loops read clearer with indices
+
+use std::cell::Cell;
+
+use crate::ecc::uint::U256;
+
+// Arithmetic mod an odd modulus m in Montgomery form, where a is stored as
+// aR mod m with R = 2^256. Multiplication then reduces with REDC, a few
+// word multiplications and shifts, instead of a long division by m.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Montgomery {
+    modulus: U256,
+    // -m^-1 mod 2^64
+    m_inv: u64,
+    // R mod m and R^2 mod m
+    r: U256,
+    r2: U256,
+}
+
+impl Montgomery {
+    // REDC needs m to be invertible mod 2^64, so m has to be odd
+    pub(crate) fn new(modulus: U256) -> Option<Self> {
+        if !modulus.is_odd() || modulus == U256::ONE {
+            return None;
+        }
+
+        // Newton's iteration doubles the correct low bits each step: 1 -> 64
+        let m0 = modulus.0[0];
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m0.wrapping_mul(inv)));
+        }
+
+        let r = U256::reduce_wide(&[0, 0, 0, 0, 1, 0, 0, 0], &modulus);
+        let r2 = r.mul_mod(&r, &modulus);
+
+        Some(Self {
+            modulus,
+            m_inv: inv.wrapping_neg(),
+            r,
+            r2,
+        })
+    }
+
+    // Same as new, but keeps the last context per thread so repeated work mod
+    // one prime (divisions, square roots) skips the two wide reductions
+    pub(crate) fn cached(modulus: U256) -> Option<Self> {
+        LAST.with(|last| {
+            if let Some(ctx) = last.get().filter(|ctx| ctx.modulus == modulus) {
+                return Some(ctx);
+            }
+
+            let ctx = Self::new(modulus)?;
+            last.set(Some(ctx));
+            Some(ctx)
+        })
+    }
+
+    // Any value below 2^256 maps to its residue in Montgomery form
+    pub(crate) fn encode(&self, a: &U256) -> U256 {
+        self.mul(a, &self.r2)
+    }
+
+    pub(crate) fn decode(&self, a: &U256) -> U256 {
+        self.mul(a, &U256::ONE)
+    }
+
+    // abR^-1 mod m, interleaving the product and the reduction (CIOS)
+    pub(crate) fn mul(&self, a: &U256, b: &U256) -> U256 {
+        let m = &self.modulus.0;
+        let mut t = [0u64; 6];
+
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let s = t[j] as u128 + a.0[j] as u128 * b.0[i] as u128 + carry;
+                t[j] = s as u64;
+                carry = s >> 64;
+            }
+            let s = t[4] as u128 + carry;
+            t[4] = s as u64;
+            t[5] = (s >> 64) as u64;
+
+            // Add the multiple of m that clears the lowest limb, then shift
+            let u = t[0].wrapping_mul(self.m_inv);
+            let s = t[0] as u128 + u as u128 * m[0] as u128;
+            let mut carry = s >> 64;
+            for j in 1..4 {
+                let s = t[j] as u128 + u as u128 * m[j] as u128 + carry;
+                t[j - 1] = s as u64;
+                carry = s >> 64;
+            }
+            let s = t[4] as u128 + carry;
+            t[3] = s as u64;
+            t[4] = t[5] + (s >> 64) as u64;
+        }
+
+        // The result is below 2m, so one subtraction is enough
+        let result = U256::from_limbs([t[0], t[1], t[2], t[3]]);
+        if t[4] != 0 || result >= self.modulus {
+            result.overflowing_sub(&self.modulus).0
+        } else {
+            result
+        }
+    }
+
+    // base and the result are in Montgomery form, the exponent isn't
+    pub(crate) fn pow(&self, base: &U256, exponent: &U256) -> U256 {
+        let mut result = self.r;
+
+        for i in (0..exponent.bits()).rev() {
+            result = self.mul(&result, &result);
+
+            if exponent.bit(i) {
+                result = self.mul(&result, base);
+            }
+        }
+
+        result
+    }
+}
+
+thread_local! {
+    static LAST: Cell<Option<Montgomery>> = const { Cell::new(None) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Montgomery;
+    use crate::ecc::secp256k1::{GX, GY, N, P};
+    use crate::ecc::uint::U256;
+
+    #[test]
+    fn matches_mul_mod() {
+        for modulus in [P, N, U256::from(223u64), U256::MAX] {
+            let ctx = Montgomery::new(modulus).unwrap();
+            let (a, b) = (GX % modulus, GY % modulus);
+
+            let product = ctx.mul(&ctx.encode(&a), &ctx.encode(&b));
+            assert_eq!(ctx.decode(&product), a.mul_mod(&b, &modulus));
+
+            let exponent = U256::from(0x1_0001u64);
+            let power = ctx.pow(&ctx.encode(&a), &exponent);
+            assert_eq!(ctx.decode(&power), a.pow_mod(&exponent, &modulus));
+
+            assert_eq!(ctx.decode(&ctx.r), U256::ONE);
+            assert_eq!(
+                ctx.decode(&ctx.encode(&(modulus - U256::ONE))),
+                modulus - U256::ONE
+            );
+        }
+    }
+
+    #[test]
+    fn even_modulus() {
+        assert!(Montgomery::new(U256::from(224u64)).is_none());
+        assert!(Montgomery::new(U256::ONE).is_none());
+        assert!(Montgomery::cached(U256::from(224u64)).is_none());
+    }
+
+    #[test]
+    fn cached_context() {
+        for modulus in [P, N, P, U256::from(223u64)] {
+            let ctx = Montgomery::cached(modulus).unwrap();
+            let fresh = Montgomery::new(modulus).unwrap();
+
+            assert_eq!(ctx.modulus, modulus);
+            assert_eq!((ctx.m_inv, ctx.r, ctx.r2), (fresh.m_inv, fresh.r, fresh.r2));
+        }
+    }
+}
@@ -0,0 +1,812 @@
+use std::fmt;
+
+use rand_core::{CryptoRng, OsRng, RngCore};
+
+use crate::ecc::constant_time::ConstantTimeEq;
+use crate::ecc::private_key::PrivateKey;
+use crate::ecc::s256_point::S256Point;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::schnorr::{challenge, has_even_y, SchnorrSignature};
+use crate::ecc::uint::U256;
+use crate::ecc::x_only_public_key::{Parity, XOnlyPublicKey};
+use crate::ecc::zeroize::Zeroize;
+use crate::error::Error;
+use crate::hash::tagged_hash;
+
+// BIP327 key aggregation: Q = sum(a_i * P_i), where each coefficient commits
+// to the whole key list so no signer can cancel out the others' keys.
+#[derive(Debug, Clone)]
+pub struct KeyAggContext {
+    public_keys: Vec<S256Point>,
+    coefficients: Vec<Scalar>,
+    aggregate_key: S256Point,
+}
+
+impl KeyAggContext {
+    pub fn new(public_keys: &[S256Point]) -> Result<Self, Error> {
+        if public_keys.is_empty() {
+            return Err(Error::InvalidInput(
+                "Can't aggregate an empty list of keys".to_string(),
+            ));
+        }
+
+        let mut serialized = Vec::with_capacity(33 * public_keys.len());
+        for key in public_keys {
+            serialized.extend_from_slice(&key.sec_compressed()?);
+        }
+        let list_hash = tagged_hash("KeyAgg list", &serialized);
+
+        // The first key that differs from the first one gets coefficient 1
+        let second_key = public_keys.iter().find(|key| **key != public_keys[0]);
+
+        let coefficients: Vec<Scalar> = public_keys
+            .iter()
+            .zip(serialized.chunks_exact(33))
+            .map(|(key, key_bytes)| {
+                if Some(key) == second_key {
+                    Scalar::ONE
+                } else {
+                    let data = [&list_hash[..], key_bytes].concat();
+                    Scalar::new(U256::from_be_bytes(&tagged_hash(
+                        "KeyAgg coefficient",
+                        &data,
+                    )))
+                }
+            })
+            .collect();
+
+        let terms: Vec<(Scalar, S256Point)> = coefficients
+            .iter()
+            .copied()
+            .zip(public_keys.iter().copied())
+            .collect();
+        let aggregate_key = S256Point::multi_mul(&terms);
+
+        if aggregate_key.is_infinity() {
+            return Err(Error::PointAtInfinity);
+        }
+
+        Ok(Self {
+            public_keys: public_keys.to_vec(),
+            coefficients,
+            aggregate_key,
+        })
+    }
+
+    pub fn aggregate_key(&self) -> S256Point {
+        self.aggregate_key
+    }
+
+    pub fn x_only_key(&self) -> XOnlyPublicKey {
+        XOnlyPublicKey::from_point(&self.aggregate_key)
+            .expect("aggregate key is never infinity")
+            .0
+    }
+
+    fn coefficient(&self, key: &S256Point) -> Option<Scalar> {
+        self.public_keys
+            .iter()
+            .position(|candidate| candidate == key)
+            .map(|i| self.coefficients[i])
+    }
+}
+
+// Secret half of a signer's nonce pair. It is deliberately neither Clone nor
+// Copy, and Session::sign takes it by value, so a nonce can only ever be used
+// for one partial signature.
+pub struct SecretNonce {
+    k1: Scalar,
+    k2: Scalar,
+    public_key: S256Point,
+}
+
+impl Drop for SecretNonce {
+    fn drop(&mut self) {
+        self.k1.zeroize();
+        self.k2.zeroize();
+    }
+}
+
+impl fmt::Debug for SecretNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretNonce")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PublicNonce {
+    pub r1: S256Point,
+    pub r2: S256Point,
+}
+
+impl PublicNonce {
+    pub fn serialize(&self) -> Result<[u8; 66], Error> {
+        let mut result = [0u8; 66];
+        result[..33].copy_from_slice(&self.r1.sec_compressed()?);
+        result[33..].copy_from_slice(&self.r2.sec_compressed()?);
+
+        Ok(result)
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != 66 {
+            return Err(Error::InvalidEncoding(format!(
+                "Public nonce is {} bytes, expected 66",
+                bytes.len()
+            )));
+        }
+
+        let r1 = S256Point::parse(&bytes[..33])?;
+        let r2 = S256Point::parse(&bytes[33..])?;
+        if r1.is_infinity() || r2.is_infinity() {
+            return Err(Error::PointAtInfinity);
+        }
+
+        Ok(Self { r1, r2 })
+    }
+}
+
+// Sum of every signer's public nonce. Unlike a single signer's nonce, either
+// point may be infinity, which is encoded as 33 zero bytes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct AggregateNonce {
+    pub r1: S256Point,
+    pub r2: S256Point,
+}
+
+impl AggregateNonce {
+    pub fn new(nonces: &[PublicNonce]) -> Result<Self, Error> {
+        if nonces.is_empty() {
+            return Err(Error::InvalidInput(
+                "Can't aggregate an empty list of nonces".to_string(),
+            ));
+        }
+
+        let (r1, r2) = nonces.iter().fold(
+            (S256Point::infinity(), S256Point::infinity()),
+            |(r1, r2), nonce| (r1 + nonce.r1, r2 + nonce.r2),
+        );
+
+        Ok(Self { r1, r2 })
+    }
+
+    pub fn serialize(&self) -> [u8; 66] {
+        let mut result = [0u8; 66];
+        result[..33].copy_from_slice(&self.r1.sec_or_zero());
+        result[33..].copy_from_slice(&self.r2.sec_or_zero());
+
+        result
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != 66 {
+            return Err(Error::InvalidEncoding(format!(
+                "Aggregate nonce is {} bytes, expected 66",
+                bytes.len()
+            )));
+        }
+
+        let point = |bytes: &[u8]| {
+            if bytes.iter().all(|&b| b == 0) {
+                Ok(S256Point::infinity())
+            } else {
+                S256Point::parse(bytes)
+            }
+        };
+
+        Ok(Self {
+            r1: point(&bytes[..33])?,
+            r2: point(&bytes[33..])?,
+        })
+    }
+}
+
+// Generates a fresh nonce pair for signing msg under the aggregate key.
+pub fn nonce_gen(
+    key: &PrivateKey,
+    key_agg: &KeyAggContext,
+    msg: &[u8],
+) -> (SecretNonce, PublicNonce) {
+    nonce_gen_with(key, key_agg, msg, &mut OsRng)
+}
+
+pub fn nonce_gen_with<R: RngCore + CryptoRng>(
+    key: &PrivateKey,
+    key_agg: &KeyAggContext,
+    msg: &[u8],
+    rng: &mut R,
+) -> (SecretNonce, PublicNonce) {
+    let mut rand = [0u8; 32];
+    rng.fill_bytes(&mut rand);
+
+    let nonces = nonce_gen_with_rand(key, key_agg, msg, &rand);
+    rand.zeroize();
+
+    nonces
+}
+
+// BIP327 NonceGen with the secret key, aggregate key and message all supplied.
+// rand must never repeat; it is hashed together with the secret key so a weak
+// source of randomness alone doesn't leak the key.
+pub fn nonce_gen_with_rand(
+    key: &PrivateKey,
+    key_agg: &KeyAggContext,
+    msg: &[u8],
+    rand: &[u8; 32],
+) -> (SecretNonce, PublicNonce) {
+    nonce_gen_internal(
+        rand,
+        Some(&key.secret()),
+        &key.public_key(),
+        Some(&key_agg.x_only_key().serialize()),
+        Some(msg),
+        &[],
+    )
+}
+
+// NonceGen exactly as BIP327 lays it out, with every optional input, so the
+// byte layout can be checked against the spec's vectors
+fn nonce_gen_internal(
+    rand: &[u8; 32],
+    secret: Option<&Scalar>,
+    public_key: &S256Point,
+    aggregate_key: Option<&[u8; 32]>,
+    msg: Option<&[u8]>,
+    extra_in: &[u8],
+) -> (SecretNonce, PublicNonce) {
+    let mut seed = *rand;
+    if let Some(secret) = secret {
+        seed = secret.to_be_bytes();
+        for (byte, mask) in seed.iter_mut().zip(tagged_hash("MuSig/aux", rand)) {
+            *byte ^= mask;
+        }
+    }
+
+    let public_key_bytes = public_key
+        .sec_compressed()
+        .expect("a signer's public key is never infinity");
+    let msg_len = msg.map_or(0, |msg| 8 + msg.len());
+    // Sized up front, including the index byte appended below, so the seed is
+    // never left behind by a realloc
+    let mut data = Vec::with_capacity(32 + 1 + 33 + 1 + 32 + 1 + msg_len + 4 + extra_in.len() + 1);
+    data.extend_from_slice(&seed);
+    seed.zeroize();
+    data.push(33);
+    data.extend_from_slice(&public_key_bytes);
+    match aggregate_key {
+        Some(aggregate_key) => {
+            data.push(32);
+            data.extend_from_slice(aggregate_key);
+        }
+        None => data.push(0),
+    }
+    match msg {
+        Some(msg) => {
+            data.push(1);
+            data.extend_from_slice(&(msg.len() as u64).to_be_bytes());
+            data.extend_from_slice(msg);
+        }
+        None => data.push(0),
+    }
+    data.extend_from_slice(&(extra_in.len() as u32).to_be_bytes());
+    data.extend_from_slice(extra_in);
+
+    let mut k = |i: u8| {
+        data.push(i);
+        let mut hash = tagged_hash("MuSig/nonce", &data);
+        data.pop();
+        let k = Scalar::new(U256::from_be_bytes(&hash));
+        hash.zeroize();
+        assert!(!k.is_zero(), "nonce is zero with negligible probability");
+        k
+    };
+    let (k1, k2) = (k(0), k(1));
+    data.zeroize();
+
+    let public_nonce = PublicNonce {
+        r1: S256Point::mul_generator(&k1),
+        r2: S256Point::mul_generator(&k2),
+    };
+    let secret_nonce = SecretNonce {
+        k1,
+        k2,
+        public_key: *public_key,
+    };
+
+    (secret_nonce, public_nonce)
+}
+
+// Everything the signers derive once every public nonce is known: the final
+// nonce R = R1 + b*R2 and the BIP340 challenge e.
+#[derive(Debug, Clone)]
+pub struct Session {
+    key_agg: KeyAggContext,
+    b: Scalar,
+    r: S256Point,
+    e: Scalar,
+}
+
+impl Session {
+    pub fn new(key_agg: &KeyAggContext, agg_nonce: &AggregateNonce, msg: &[u8]) -> Self {
+        let q_bytes = key_agg.x_only_key().serialize();
+        let data = [&agg_nonce.serialize()[..], &q_bytes, msg].concat();
+        let b = Scalar::new(U256::from_be_bytes(&tagged_hash("MuSig/noncecoef", &data)));
+
+        let mut r = agg_nonce.r1 + agg_nonce.r2.mul_vartime(&b);
+        if r.is_infinity() {
+            // Only possible if a signer is malicious; BIP327 falls back to G
+            r = S256Point::generator();
+        }
+
+        let r_bytes = r.x().expect("R isn't infinity").to_be_bytes();
+        let e = challenge(&r_bytes, &q_bytes, msg);
+
+        Self {
+            key_agg: key_agg.clone(),
+            b,
+            r,
+            e,
+        }
+    }
+
+    // Round two. Consumes the secret nonce so it can't sign a second message.
+    pub fn sign(&self, secret_nonce: SecretNonce, key: &PrivateKey) -> Result<Scalar, Error> {
+        let public_key = key.public_key();
+        if secret_nonce.public_key != public_key {
+            return Err(Error::InvalidInput(
+                "Secret nonce was generated for a different key".to_string(),
+            ));
+        }
+
+        let a = self.key_agg.coefficient(&public_key).ok_or_else(|| {
+            Error::InvalidInput("Signer's key isn't part of the aggregate key".to_string())
+        })?;
+
+        // Flip the nonces and the key so R and Q end up with even y
+        let (k1, k2) = if has_even_y(&self.r) {
+            (secret_nonce.k1, secret_nonce.k2)
+        } else {
+            (-secret_nonce.k1, -secret_nonce.k2)
+        };
+        let d = self.key_sign() * key.secret();
+
+        Ok(k1 + self.b * k2 + self.e * a * d)
+    }
+
+    // s_i * G == R1_i + b * R2_i + e * a_i * P_i, adjusted for the parities of
+    // R and Q the same way sign adjusts the secrets.
+    pub fn verify_partial(
+        &self,
+        partial: Scalar,
+        public_nonce: &PublicNonce,
+        public_key: &S256Point,
+    ) -> bool {
+        let a = match self.key_agg.coefficient(public_key) {
+            Some(a) => a,
+            None => return false,
+        };
+
+        let r_sign = if has_even_y(&self.r) {
+            Scalar::ONE
+        } else {
+            -Scalar::ONE
+        };
+        let expected = (public_nonce.r1 + public_nonce.r2.mul_vartime(&self.b))
+            .mul_vartime(&r_sign)
+            + public_key.mul_vartime(&(self.e * a * self.key_sign()));
+
+        S256Point::mul_generator(&partial).ct_eq(&expected)
+    }
+
+    pub fn aggregate(&self, partials: &[Scalar]) -> SchnorrSignature {
+        let s = partials.iter().fold(Scalar::ZERO, |acc, s| acc + *s);
+
+        SchnorrSignature {
+            r: self.r.x().expect("R isn't infinity"),
+            s,
+        }
+    }
+
+    fn key_sign(&self) -> Scalar {
+        match XOnlyPublicKey::from_point(&self.key_agg.aggregate_key) {
+            Ok((_, Parity::Odd)) => -Scalar::ONE,
+            _ => Scalar::ONE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        nonce_gen, nonce_gen_internal, nonce_gen_with_rand, AggregateNonce, KeyAggContext,
+        PublicNonce, SecretNonce, Session,
+    };
+    use crate::ecc::private_key::PrivateKey;
+    use crate::ecc::s256_point::S256Point;
+    use crate::ecc::scalar::Scalar;
+    use crate::ecc::schnorr::verify;
+    use crate::error::Error;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn point(s: &str) -> S256Point {
+        S256Point::parse(&unhex(s)).unwrap()
+    }
+
+    fn scalar(s: &str) -> Scalar {
+        Scalar::from_be_bytes(&unhex(s).try_into().unwrap()).unwrap()
+    }
+
+    fn keys(secrets: &[u64]) -> Vec<PrivateKey> {
+        secrets
+            .iter()
+            .map(|secret| PrivateKey::new(Scalar::from_u64(*secret)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn key_aggregation() {
+        let keys = keys(&[1, 2, 3]);
+        let public_keys: Vec<_> = keys.iter().map(|key| key.public_key()).collect();
+
+        let ctx = KeyAggContext::new(&public_keys).unwrap();
+        assert_eq!(ctx.coefficient(&public_keys[1]), Some(Scalar::ONE));
+        assert_ne!(ctx.coefficient(&public_keys[0]), Some(Scalar::ONE));
+
+        // Order matters, and the plain sum of keys isn't used
+        let reversed: Vec<_> = public_keys.iter().rev().copied().collect();
+        assert_ne!(
+            KeyAggContext::new(&reversed).unwrap().aggregate_key(),
+            ctx.aggregate_key()
+        );
+        assert_ne!(
+            ctx.aggregate_key(),
+            public_keys[0] + public_keys[1] + public_keys[2]
+        );
+
+        assert!(KeyAggContext::new(&[]).is_err());
+    }
+
+    #[test]
+    fn two_round_signing() {
+        let keys = keys(&[3, 0x1234, 0xdeadbeef]);
+        let public_keys: Vec<_> = keys.iter().map(|key| key.public_key()).collect();
+        let ctx = KeyAggContext::new(&public_keys).unwrap();
+        let msg = b"MuSig2 test message";
+
+        // Round one: every signer shares a public nonce
+        let (secret_nonces, public_nonces): (Vec<_>, Vec<_>) =
+            keys.iter().map(|key| nonce_gen(key, &ctx, msg)).unzip();
+        let agg_nonce = AggregateNonce::new(&public_nonces).unwrap();
+
+        // Round two: partial signatures, checked individually then combined
+        let session = Session::new(&ctx, &agg_nonce, msg);
+        let partials: Vec<Scalar> = secret_nonces
+            .into_iter()
+            .zip(&keys)
+            .map(|(secret_nonce, key)| session.sign(secret_nonce, key).unwrap())
+            .collect();
+
+        for ((partial, nonce), public_key) in partials.iter().zip(&public_nonces).zip(&public_keys)
+        {
+            assert!(session.verify_partial(*partial, nonce, public_key));
+        }
+        assert!(!session.verify_partial(partials[0], &public_nonces[1], &public_keys[0]));
+
+        let sig = session.aggregate(&partials);
+        assert!(verify(&ctx.x_only_key(), msg, &sig));
+        assert!(!verify(&ctx.x_only_key(), b"another message", &sig));
+    }
+
+    #[test]
+    fn sign_rejects_mismatched_keys() {
+        let keys = keys(&[5, 6, 7]);
+        let ctx = KeyAggContext::new(&[keys[0].public_key(), keys[1].public_key()]).unwrap();
+        let msg = b"msg";
+
+        let (secret_nonce, public_nonce) = nonce_gen_with_rand(&keys[0], &ctx, msg, &[1u8; 32]);
+        let session = Session::new(&ctx, &AggregateNonce::new(&[public_nonce]).unwrap(), msg);
+        assert!(session.sign(secret_nonce, &keys[1]).is_err());
+
+        // Key 7 isn't one of the aggregated keys
+        let (secret_nonce, _) = nonce_gen_with_rand(&keys[2], &ctx, msg, &[1u8; 32]);
+        assert!(session.sign(secret_nonce, &keys[2]).is_err());
+    }
+
+    #[test]
+    fn nonce_serialization() {
+        let key = PrivateKey::new(Scalar::from_u64(9)).unwrap();
+        let ctx = KeyAggContext::new(&[key.public_key()]).unwrap();
+
+        let (_, public_nonce) = nonce_gen_with_rand(&key, &ctx, b"", &[2u8; 32]);
+        assert_eq!(
+            PublicNonce::parse(&public_nonce.serialize().unwrap()).unwrap(),
+            public_nonce
+        );
+        assert!(PublicNonce::parse(&[0u8; 66]).is_err());
+
+        let infinite = PublicNonce {
+            r1: public_nonce.r1,
+            r2: S256Point::infinity(),
+        };
+        assert_eq!(infinite.serialize(), Err(Error::PointAtInfinity));
+
+        // Different rand gives a different nonce
+        let (_, other) = nonce_gen_with_rand(&key, &ctx, b"", &[3u8; 32]);
+        assert_ne!(other, public_nonce);
+    }
+
+    // Public keys from the BIP327 key aggregation vectors; the last three are
+    // invalid: x not on the curve, x >= p, and a bad prefix
+    const KEY_AGG_KEYS: [&str; 6] = [
+        "02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+        "03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+        "023590A94E768F8E1815C2F24B4D80A8E3149316C3518CE7B7AD338368D038CA66",
+        "020000000000000000000000000000000000000000000000000000000000000005",
+        "02FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30",
+        "04F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+    ];
+
+    #[test]
+    fn bip327_key_aggregation() {
+        let vectors: [(&[usize], &str); 4] = [
+            (
+                &[0, 1, 2],
+                "90539EEDE565F5D054F32CC0C220126889ED1E5D193BAF15AEF344FE59D4610C",
+            ),
+            (
+                &[2, 1, 0],
+                "6204DE8B083426DC6EAF9502D27024D53FC826BF7D2012148A0575435DF54B2B",
+            ),
+            (
+                &[0, 0, 0],
+                "B436E3BAD62B8CD409969A224731C193D051162D8C5AE8B109306127DA3AA935",
+            ),
+            (
+                &[0, 0, 1, 1],
+                "69BC22BFA5D106306E48A20679DE1D7389386124D07571D0D872686028C26A3E",
+            ),
+        ];
+
+        for (indices, expected) in vectors {
+            let public_keys: Vec<_> = indices.iter().map(|&i| point(KEY_AGG_KEYS[i])).collect();
+            let ctx = KeyAggContext::new(&public_keys).unwrap();
+            assert_eq!(ctx.x_only_key().serialize().to_vec(), unhex(expected));
+        }
+
+        for invalid in &KEY_AGG_KEYS[3..] {
+            assert!(S256Point::parse(&unhex(invalid)).is_err());
+        }
+    }
+
+    #[test]
+    fn bip327_nonce_gen() {
+        // Every optional input present
+        let public_key =
+            point("024D4B6CD1361032CA9BD2AEB9D900AA4D45D9EAD80AC9423374C451A7254D0766");
+        let (secret_nonce, public_nonce) = nonce_gen_internal(
+            &[0x0F; 32],
+            Some(&scalar(
+                "0202020202020202020202020202020202020202020202020202020202020202",
+            )),
+            &public_key,
+            Some(&[0x07; 32]),
+            Some(&[0x01; 32]),
+            &[0x08; 32],
+        );
+        assert_eq!(
+            secret_nonce.k1.to_be_bytes().to_vec(),
+            unhex("B114E502BEAA4E301DD08A50264172C84E41650E6CB726B410C0694D59EFFB64")
+        );
+        assert_eq!(
+            secret_nonce.k2.to_be_bytes().to_vec(),
+            unhex("95B5CAF28D045B973D63E3C99A44B807BDE375FD6CB39E46DC4A511708D0E9D2")
+        );
+        assert_eq!(secret_nonce.public_key, public_key);
+        assert_eq!(
+            public_nonce.serialize().unwrap().to_vec(),
+            unhex(
+                "02F7BE7089E8376EB355272368766B17E88E7DB72047D05E56AA881EA52B3B35DF\
+                 02C29C8046FDD0DED4C7E55869137200FBDBFE2EB654267B6D7013602CAED3115A"
+            )
+        );
+
+        // Only rand and the public key
+        let public_key =
+            point("02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9");
+        let (secret_nonce, public_nonce) =
+            nonce_gen_internal(&[0x0F; 32], None, &public_key, None, None, &[]);
+        assert_eq!(
+            secret_nonce.k1.to_be_bytes().to_vec(),
+            unhex("89BDD787D0284E5E4D5FC572E49E316BAB7E21E3B1830DE37DFE80156FA41A6D")
+        );
+        assert_eq!(
+            secret_nonce.k2.to_be_bytes().to_vec(),
+            unhex("0B17AE8D024C53679699A6FD7944D9C4A366B514BAF43088E0708B1023DD2897")
+        );
+        assert_eq!(
+            public_nonce.serialize().unwrap().to_vec(),
+            unhex(
+                "02C96E7CB1E8AA5DAC64D872947914198F607D90ECDE5200DE52978AD5DED63C00\
+                 0299EC5117C2D29EDEE8A2092587C3909BE694D5CFF0667D6C02EA4059F7CD9786"
+            )
+        );
+    }
+
+    #[test]
+    fn bip327_nonce_aggregation() {
+        let nonces = [
+            "020151C80F435648DF67A22B749CD798CE54E0321D034B92B709B567D60A42E666\
+             03BA47FBC1834437B3212E89A84D8425E7BF12E0245D98262268EBDCB385D50641",
+            "03FF406FFD8ADB9CD29877E4985014F66A59F6CD01C0E88CAA8E5F3166B1F676A6\
+             0248C264CDD57D3C24D79990B0F865674EB62A0F9018277A95011B41BFC193B833",
+            "020151C80F435648DF67A22B749CD798CE54E0321D034B92B709B567D60A42E666\
+             0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",
+            "03FF406FFD8ADB9CD29877E4985014F66A59F6CD01C0E88CAA8E5F3166B1F676A6\
+             0379BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",
+        ];
+        let nonce = |i: usize| PublicNonce::parse(&unhex(nonces[i])).unwrap();
+
+        // The second pair's R2 points cancel out, leaving infinity
+        let vectors = [
+            (
+                [0, 1],
+                "035FE1873B4F2967F52FEA4A06AD5A8ECCBE9D0FD73068012C894E2E87CCB5804B\
+                 024725377345BDE0E9C33AF3C43C0A29A9249F2F2956FA8CFEB55C8573D0262DC8",
+            ),
+            (
+                [2, 3],
+                "035FE1873B4F2967F52FEA4A06AD5A8ECCBE9D0FD73068012C894E2E87CCB5804B\
+                 000000000000000000000000000000000000000000000000000000000000000000",
+            ),
+        ];
+        for ([i, j], expected) in vectors {
+            let agg_nonce = AggregateNonce::new(&[nonce(i), nonce(j)]).unwrap();
+            assert_eq!(agg_nonce.serialize().to_vec(), unhex(expected));
+            assert_eq!(AggregateNonce::parse(&unhex(expected)).unwrap(), agg_nonce);
+        }
+
+        // Bad prefix, x not on the curve, x >= p
+        let invalid = [
+            "04FF406FFD8ADB9CD29877E4985014F66A59F6CD01C0E88CAA8E5F3166B1F676A6\
+             0248C264CDD57D3C24D79990B0F865674EB62A0F9018277A95011B41BFC193B833",
+            "03FF406FFD8ADB9CD29877E4985014F66A59F6CD01C0E88CAA8E5F3166B1F676A6\
+             0248C264CDD57D3C24D79990B0F865674EB62A0F9018277A95011B41BFC193B831",
+            "03FF406FFD8ADB9CD29877E4985014F66A59F6CD01C0E88CAA8E5F3166B1F676A6\
+             02FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30",
+        ];
+        for nonce in invalid {
+            assert!(PublicNonce::parse(&unhex(nonce)).is_err());
+        }
+    }
+
+    // The BIP327 sign/verify vectors. The signer is key 0 with public nonce 0.
+    const SIGN_KEYS: [&str; 4] = [
+        "03935F972DA013F80AE011890FA89B67A27B7BE6CCB24D3274D18B2D4067F261A9",
+        "02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+        "02DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA661",
+        "020000000000000000000000000000000000000000000000000000000000000007",
+    ];
+
+    const SIGN_NONCES: [&str; 5] = [
+        "0337C87821AFD50A8644D820A8F3E02E499C931865C2360FB43D0A0D20DAFE07EA\
+         0287BF891D2A6DEAEBADC909352AA9405D1428C15F4B75F04DAE642A95C2548480",
+        "0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798\
+         0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",
+        "032DE2662628C90B03F5E720284EB52FF7D71F4284F627B68A853D78C78E1FFE93\
+         03E4C5524E83FFE1493B9077CF1CA6BEB2090C93D930321071AD40B2F44E599046",
+        "0237C87821AFD50A8644D820A8F3E02E499C931865C2360FB43D0A0D20DAFE07EA\
+         0387BF891D2A6DEAEBADC909352AA9405D1428C15F4B75F04DAE642A95C2548480",
+        "020000000000000000000000000000000000000000000000000000000000000009\
+         0287BF891D2A6DEAEBADC909352AA9405D1428C15F4B75F04DAE642A95C2548480",
+    ];
+
+    const SIGN_AGG_NONCES: [&str; 5] = [
+        "028465FCF0BBDBCF443AABCCE533D42B4B5A10966AC09A49655E8C42DAAB8FCD61\
+         037496A3CC86926D452CAFCFD55D25972CA1675D549310DE296BFF42F72EEEA8C9",
+        "000000000000000000000000000000000000000000000000000000000000000000\
+         000000000000000000000000000000000000000000000000000000000000000000",
+        "048465FCF0BBDBCF443AABCCE533D42B4B5A10966AC09A49655E8C42DAAB8FCD61\
+         037496A3CC86926D452CAFCFD55D25972CA1675D549310DE296BFF42F72EEEA8C9",
+        "028465FCF0BBDBCF443AABCCE533D42B4B5A10966AC09A49655E8C42DAAB8FCD61\
+         020000000000000000000000000000000000000000000000000000000000000009",
+        "028465FCF0BBDBCF443AABCCE533D42B4B5A10966AC09A49655E8C42DAAB8FCD61\
+         02FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30",
+    ];
+
+    const SIGN_MSG: &str = "F95466D086770E689964664219266FE5ED215C92AE20BAB5C9D79ADDDDF3C0CF";
+
+    fn sign_session(key_indices: &[usize], agg_nonce: &AggregateNonce) -> Session {
+        let public_keys: Vec<_> = key_indices.iter().map(|&i| point(SIGN_KEYS[i])).collect();
+        let ctx = KeyAggContext::new(&public_keys).unwrap();
+        Session::new(&ctx, agg_nonce, &unhex(SIGN_MSG))
+    }
+
+    #[test]
+    fn bip327_sign_verify() {
+        let key = PrivateKey::new(scalar(
+            "7FB9E0E687ADA1EEBF7ECFE2F21E73EBDB51A7D450948DFE8D76D7F2D1007671",
+        ))
+        .unwrap();
+        assert_eq!(key.public_key(), point(SIGN_KEYS[0]));
+        let secret_nonce = || SecretNonce {
+            k1: scalar("508B81A611F100A6B2B6B29656590898AF488BCF2E1F55CF22E5CFB84421FE61"),
+            k2: scalar("FA27FD49B1D50085B481285E1CA205D55C82CC1B31FF5CD54A489829355901F7"),
+            public_key: key.public_key(),
+        };
+        let nonce = |i: usize| PublicNonce::parse(&unhex(SIGN_NONCES[i])).unwrap();
+
+        // Key order, nonce order, aggregate nonce, expected partial signature.
+        // The last one's nonces cancel out, so R falls back to G.
+        let vectors: [(&[usize], &[usize], usize, &str); 4] = [
+            (
+                &[0, 1, 2],
+                &[0, 1, 2],
+                0,
+                "012ABBCB52B3016AC03AD82395A1A415C48B93DEF78718E62A7A90052FE224FB",
+            ),
+            (
+                &[1, 0, 2],
+                &[1, 0, 2],
+                0,
+                "9FF2F7AAA856150CC8819254218D3ADEEB0535269051897724F9DB3789513A52",
+            ),
+            (
+                &[1, 2, 0],
+                &[1, 2, 0],
+                0,
+                "FA23C359F6FAC4E7796BB93BC9F0532A95468C539BA20FF86D7C76ED92227900",
+            ),
+            (
+                &[0, 1],
+                &[0, 3],
+                1,
+                "AE386064B26105404798F75DE2EB9AF5EDA5387B064B83D049CB7C5E08879531",
+            ),
+        ];
+
+        for (key_indices, nonce_indices, agg_index, expected) in vectors {
+            let nonces: Vec<_> = nonce_indices.iter().map(|&i| nonce(i)).collect();
+            let agg_nonce = AggregateNonce::parse(&unhex(SIGN_AGG_NONCES[agg_index])).unwrap();
+            assert_eq!(AggregateNonce::new(&nonces).unwrap(), agg_nonce);
+
+            let session = sign_session(key_indices, &agg_nonce);
+            let partial = session.sign(secret_nonce(), &key).unwrap();
+            assert_eq!(partial.to_be_bytes().to_vec(), unhex(expected));
+            assert!(session.verify_partial(partial, &nonce(0), &key.public_key()));
+        }
+
+        // The signer's key isn't in the list, or the list holds an invalid key
+        let agg_nonce = AggregateNonce::parse(&unhex(SIGN_AGG_NONCES[0])).unwrap();
+        assert!(sign_session(&[1, 2], &agg_nonce)
+            .sign(secret_nonce(), &key)
+            .is_err());
+        assert!(S256Point::parse(&unhex(SIGN_KEYS[3])).is_err());
+
+        // Aggregate nonces with a bad prefix, x not on the curve, x >= p
+        for agg_nonce in &SIGN_AGG_NONCES[2..] {
+            assert!(AggregateNonce::parse(&unhex(agg_nonce)).is_err());
+        }
+
+        // Partial signatures that don't verify: a negated one, one checked
+        // against the wrong signer, and one that's out of range
+        let session = sign_session(&[0, 1, 2], &agg_nonce);
+        let negated = scalar("FED54434AD4CFE953FC527DC6A5E5BE8F6234907B7C187559557CE87A0541C46");
+        assert!(!session.verify_partial(negated, &nonce(0), &key.public_key()));
+        let partial = scalar("012ABBCB52B3016AC03AD82395A1A415C48B93DEF78718E62A7A90052FE224FB");
+        assert!(!session.verify_partial(partial, &nonce(1), &point(SIGN_KEYS[1])));
+        let out_of_range =
+            unhex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+        assert!(Scalar::from_be_bytes(&out_of_range.try_into().unwrap()).is_err());
+
+        // A signer's public nonce that isn't on the curve
+        assert!(PublicNonce::parse(&unhex(SIGN_NONCES[4])).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use std::ops::{Add, Neg, Sub};
+use std::sync::LazyLock;
+
+use crate::ecc::constant_time::ConstantTimeEq;
+use crate::ecc::s256_point::S256Point;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::x_only_public_key::XOnlyPublicKey;
+use crate::error::Error;
+use crate::hash::sha256::Sha256;
+
+// Second generator with no known discrete log relative to G: the even-y point
+// whose x is SHA256 of G's uncompressed encoding. The hash happens to be a
+// valid x coordinate, and it's the same H as libsecp256k1-zkp uses.
+static H: LazyLock<S256Point> = LazyLock::new(|| {
+    let x = Sha256::digest(
+        &S256Point::generator()
+            .sec_uncompressed()
+            .expect("G is not infinity"),
+    );
+    XOnlyPublicKey::parse(&x)
+        .expect("hash of G is a valid x coordinate")
+        .to_point()
+});
+
+// Pedersen commitment r*G + v*H to a value v with blinding factor r. It hides
+// v as long as r is random and binds to it as long as log_G(H) is unknown.
+// Commitments add and subtract like the values and blinding factors they
+// commit to, so sums can be checked without opening them.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Commitment(S256Point);
+
+impl Commitment {
+    pub fn commit(value: u64, blinding: &Scalar) -> Self {
+        Self(S256Point::mul_generator(blinding) + *H * Scalar::from_u64(value))
+    }
+
+    pub fn generator_h() -> S256Point {
+        *H
+    }
+
+    pub fn point(&self) -> S256Point {
+        self.0
+    }
+
+    pub fn verify(&self, value: u64, blinding: &Scalar) -> bool {
+        self.0.ct_eq(&Self::commit(value, blinding).0)
+    }
+
+    // A commitment to zero with a zero blinding factor is the point at
+    // infinity, which has no SEC encoding
+    pub fn serialize(&self) -> Result<[u8; 33], Error> {
+        self.0.sec_compressed()
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        S256Point::parse(bytes).map(Self)
+    }
+}
+
+impl Add for Commitment {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for Commitment {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl Neg for Commitment {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Commitment;
+    use crate::ecc::s256_field::S256Field;
+    use crate::ecc::s256_point::S256Point;
+    use crate::ecc::scalar::Scalar;
+    use crate::ecc::uint::U256;
+    use crate::error::Error;
+
+    #[test]
+    fn nums_generator() {
+        let h = S256Point::new(
+            S256Field::new(U256::from_be_hex(
+                "50929B74C1A04954B78B4B6035E97A5E078A5A0F28EC96D547BFEE9ACE803AC0",
+            )),
+            S256Field::new(U256::from_be_hex(
+                "31D3C6863973926E049E637CB1B5F40A36DAC28AF1766968C30C2313F3A38904",
+            )),
+        )
+        .unwrap();
+
+        assert_eq!(Commitment::generator_h(), h);
+    }
+
+    #[test]
+    fn open() {
+        let blinding = Scalar::from_u64(0xb11d);
+        let c = Commitment::commit(21_000_000, &blinding);
+
+        assert!(c.verify(21_000_000, &blinding));
+        assert!(!c.verify(21_000_001, &blinding));
+        assert!(!c.verify(21_000_000, &Scalar::from_u64(0xb11e)));
+    }
+
+    #[test]
+    fn homomorphic() {
+        let (r1, r2) = (Scalar::from_u64(111), Scalar::from_u64(222));
+        let a = Commitment::commit(30, &r1);
+        let b = Commitment::commit(12, &r2);
+
+        assert_eq!(a + b, Commitment::commit(42, &(r1 + r2)));
+        assert_eq!(a - b, Commitment::commit(18, &(r1 - r2)));
+        assert_eq!(a + -a, Commitment::commit(0, &Scalar::ZERO));
+
+        // Inputs balance outputs when their blinding factors do as well
+        let r3 = r1 + r2 - Scalar::from_u64(5);
+        let inputs = a + b;
+        let outputs = Commitment::commit(40, &r3) + Commitment::commit(2, &Scalar::from_u64(5));
+        assert!((inputs - outputs).point().is_infinity());
+    }
+
+    #[test]
+    fn serialization() {
+        let c = Commitment::commit(7, &Scalar::from_u64(9));
+        let bytes = c.serialize().unwrap();
+
+        assert_eq!(bytes.len(), 33);
+        assert_eq!(Commitment::parse(&bytes).unwrap(), c);
+
+        let zero = Commitment::commit(0, &Scalar::ZERO);
+        assert_eq!(zero.serialize(), Err(Error::PointAtInfinity));
+        assert!(Commitment::parse(&[0x00]).is_err());
+    }
+}
@@ -1,82 +1,145 @@
 use std::fmt;
-use std::ops::{Add, Mul};
+use std::ops::{Add, Mul, Neg, Sub};
 
 use crate::ecc::field_element::FieldElement;
+use crate::ecc::uint::U256;
+use crate::error::Error;
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Coordinates {
+    Infinity,
+    Affine { x: FieldElement, y: FieldElement },
+}
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub struct Point {
     a: FieldElement,
     b: FieldElement,
-    x: Option<FieldElement>,
-    y: Option<FieldElement>,
+    coordinates: Coordinates,
 }
 
 impl Point {
+    // Infinity is written as (None, None), as in the book. Passing only one
+    // of the coordinates is an error.
     pub fn new(
         x: Option<FieldElement>,
         y: Option<FieldElement>,
         a: FieldElement,
         b: FieldElement,
-    ) -> Result<Self, String> {
-        if x.is_some() && y.is_some() {
-            let y2 = y.unwrap().field_power(2)?;
-            let x3 = x.unwrap().field_power(3)?;
-            let a = (a * x.unwrap())?;
-            let rhs = (x3 + (a + b)?)?;
-
-            if y2 != rhs {
-                return Err(format!(
-                    "({},{}) is not on the curve",
-                    x.unwrap(),
-                    y.unwrap()
-                ));
-            }
+    ) -> Result<Self, Error> {
+        match (x, y) {
+            (Some(x), Some(y)) => Self::affine(x, y, a, b),
+            (None, None) => Ok(Self::infinity(a, b)),
+            _ => Err(Error::InvalidInput(
+                "A point needs both coordinates or neither".to_string(),
+            )),
+        }
+    }
+
+    pub fn affine(
+        x: FieldElement,
+        y: FieldElement,
+        a: FieldElement,
+        b: FieldElement,
+    ) -> Result<Self, Error> {
+        let y2 = y.field_power(2)?;
+        let x3 = x.field_power(3)?;
+        let ax = (a * x)?;
+        let rhs = (x3 + (ax + b)?)?;
+
+        if y2 != rhs {
+            return Err(Error::NotOnCurve);
         }
 
-        Ok(Self { a, b, x, y })
+        Ok(Self::new_unchecked(x, y, a, b))
+    }
+
+    pub fn infinity(a: FieldElement, b: FieldElement) -> Self {
+        Self {
+            a,
+            b,
+            coordinates: Coordinates::Infinity,
+        }
+    }
+
+    // For coordinates that are on the curve by construction, e.g. the result
+    // of arithmetic on points that already passed the check in new.
+    pub(crate) fn new_unchecked(
+        x: FieldElement,
+        y: FieldElement,
+        a: FieldElement,
+        b: FieldElement,
+    ) -> Self {
+        Self {
+            a,
+            b,
+            coordinates: Coordinates::Affine { x, y },
+        }
+    }
+
+    pub fn coordinates(&self) -> Coordinates {
+        self.coordinates
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.coordinates == Coordinates::Infinity
+    }
+
+    pub fn x(&self) -> Option<FieldElement> {
+        match self.coordinates {
+            Coordinates::Affine { x, .. } => Some(x),
+            Coordinates::Infinity => None,
+        }
+    }
+
+    pub fn y(&self) -> Option<FieldElement> {
+        match self.coordinates {
+            Coordinates::Affine { y, .. } => Some(y),
+            Coordinates::Infinity => None,
+        }
+    }
+
+    pub fn a(&self) -> FieldElement {
+        self.a
+    }
+
+    pub fn b(&self) -> FieldElement {
+        self.b
     }
 }
 
 impl Add for Point {
-    type Output = Result<Point, String>;
+    type Output = Result<Point, Error>;
 
     fn add(self, other: Self) -> Self::Output {
         if self.a != other.a || self.b != other.b {
-            return Err(format!(
-                "Points {}, {} are not on the same curve",
-                self, other
-            ));
+            return Err(Error::CurveMismatch);
         }
 
-        if self.x.is_none() {
-            Ok(other)
-        } else if other.x.is_none() {
-            Ok(self)
-        } else if self.x == other.x && self.y != other.y {
-            Point::new(None, None, self.a, self.b)
-        } else if self.x != other.x {
-            let x1 = self.x.unwrap();
-            let x2 = other.x.unwrap();
-
-            let y1 = self.y.unwrap();
-            let y2 = other.y.unwrap();
+        let (x1, y1, x2, y2) = match (self.coordinates, other.coordinates) {
+            (Coordinates::Infinity, _) => return Ok(other),
+            (_, Coordinates::Infinity) => return Ok(self),
+            (Coordinates::Affine { x: x1, y: y1 }, Coordinates::Affine { x: x2, y: y2 }) => {
+                (x1, y1, x2, y2)
+            }
+        };
 
+        if x1 == x2 && y1 != y2 {
+            Ok(Point::infinity(self.a, self.b))
+        } else if x1 != x2 {
             let s = ((y2 - y1)? / (x2 - x1)?)?;
 
             let x3 = ((s.field_power(2)? - x1)? - x2)?;
             let y3 = ((s * (x1 - x3)?)? - y1)?;
 
-            Point::new(Some(x3), Some(y3), self.a, self.b)
-        } else if self == other && self.y.unwrap().num == 0 {
+            Point::affine(x3, y3, self.a, self.b)
+        } else if y1.num.is_zero() {
             // Points are equal and y coordinate is zero.
             // We can't calculate slope here
-            Point::new(None, None, self.a, self.b)
-        } else if self == other {
-            let x1 = self.x.unwrap();
-            let y1 = self.y.unwrap();
-
-            let c_3 = FieldElement::new(3, x1.prime)?;
-            let c_2 = FieldElement::new(2, x1.prime)?;
+            Ok(Point::infinity(self.a, self.b))
+        } else {
+            let c_3 = FieldElement::new(U256::from(3u64), x1.prime)?;
+            let c_2 = FieldElement::new(U256::from(2u64), x1.prime)?;
             let p1 = ((c_3 * (x1.field_power(2)?))? + self.a)?;
             let p2 = (c_2 * y1)?;
             let s = (p1 / p2)?;
@@ -84,81 +147,142 @@ impl Add for Point {
             let x3 = (s.field_power(2)? - (c_2 * x1)?)?;
             let y3 = ((s * (x1 - x3)?)? - y1)?;
 
-            Point::new(Some(x3), Some(y3), self.a, self.b)
-        } else {
-            Err("".to_string())
+            Point::affine(x3, y3, self.a, self.b)
+        }
+    }
+}
+
+// Reflects the point over the x axis. Infinity is its own negation.
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Self::Output {
+        let coordinates = match self.coordinates {
+            Coordinates::Affine { x, y } => Coordinates::Affine {
+                x,
+                y: FieldElement {
+                    num: U256::ZERO.sub_mod(&y.num, &y.prime),
+                    prime: y.prime,
+                },
+            },
+            Coordinates::Infinity => Coordinates::Infinity,
+        };
+
+        Self {
+            coordinates,
+            ..self
         }
     }
 }
 
-impl Mul<i32> for Point {
-    type Output = Result<Self, String>;
+impl Sub for Point {
+    type Output = Result<Point, Error>;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self + -other
+    }
+}
+
+impl Add for &Point {
+    type Output = Result<Point, Error>;
 
-    fn mul(self, mut coef: i32) -> Self::Output {
-        let mut current = self.clone();
-        let mut result = Self::new(None, None, self.a, self.b)?;
+    fn add(self, other: Self) -> Self::Output {
+        *self + *other
+    }
+}
 
-        while coef > 0 {
-            if coef & 1 != 0 {
+impl Sub for &Point {
+    type Output = Result<Point, Error>;
+
+    fn sub(self, other: Self) -> Self::Output {
+        *self - *other
+    }
+}
+
+impl Mul<U256> for Point {
+    type Output = Result<Self, Error>;
+
+    // Double-and-add over the binary expansion of the coefficient
+    fn mul(self, mut coef: U256) -> Self::Output {
+        let mut current = self;
+        let mut result = Self::infinity(self.a, self.b);
+
+        while !coef.is_zero() {
+            if coef.is_odd() {
                 result = (result + current)?;
             }
 
-            current = (current.clone() + current)?;
-            coef >>= 1;
+            current = (current + current)?;
+            coef = coef >> 1;
         }
 
         Ok(result)
     }
 }
 
+impl Mul<u64> for Point {
+    type Output = Result<Self, Error>;
+
+    fn mul(self, coef: u64) -> Self::Output {
+        self * U256::from(coef)
+    }
+}
+
+impl Mul<U256> for &Point {
+    type Output = Result<Point, Error>;
+
+    fn mul(self, coef: U256) -> Self::Output {
+        *self * coef
+    }
+}
+
 impl fmt::Display for Point {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "({}, {}, {}, {})",
-            self.x.unwrap(),
-            self.y.unwrap(),
-            self.a,
-            self.b
-        )
+        match self.coordinates {
+            Coordinates::Affine { x, y } => write!(f, "({}, {}, {}, {})", x, y, self.a, self.b),
+            Coordinates::Infinity => write!(f, "(infinity, {}, {})", self.a, self.b),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::ecc::field_element::FieldElement;
+    use crate::ecc::secp256k1::{A, B, G, N, P};
+    use crate::ecc::uint::U256;
+    use crate::error::Error;
 
     use super::Point;
 
     #[test]
     fn setup() {
         let prime = 223;
-        let a = FieldElement::new(5, prime).unwrap();
-        let b = FieldElement::new(7, prime).unwrap();
+        let a = FieldElement::from_i64(5, prime).unwrap();
+        let b = FieldElement::from_i64(7, prime).unwrap();
 
-        let x1 = Some(FieldElement::new(-1, prime).unwrap());
-        let y1 = Some(FieldElement::new(-1, prime).unwrap());
+        let x1 = Some(FieldElement::from_i64(-1, prime).unwrap());
+        let y1 = Some(FieldElement::from_i64(-1, prime).unwrap());
 
-        let x2 = Some(FieldElement::new(-1, prime).unwrap());
-        let y2 = Some(FieldElement::new(-2, prime).unwrap());
+        let x2 = Some(FieldElement::from_i64(-1, prime).unwrap());
+        let y2 = Some(FieldElement::from_i64(-2, prime).unwrap());
 
         let p1 = Point::new(x1, y1, a, b);
         let p2 = Point::new(x2, y2, a, b);
 
         assert!(p1.is_ok());
-        assert!(p2.is_err());
+        assert_eq!(p2, Err(Error::NotOnCurve));
 
-        let x1 = Some(FieldElement::new(2, prime).unwrap());
-        let y1 = Some(FieldElement::new(4, prime).unwrap());
+        let x1 = Some(FieldElement::from_i64(2, prime).unwrap());
+        let y1 = Some(FieldElement::from_i64(4, prime).unwrap());
 
-        let x2 = Some(FieldElement::new(-1, prime).unwrap());
-        let y2 = Some(FieldElement::new(-1, prime).unwrap());
+        let x2 = Some(FieldElement::from_i64(-1, prime).unwrap());
+        let y2 = Some(FieldElement::from_i64(-1, prime).unwrap());
 
-        let x3 = Some(FieldElement::new(18, prime).unwrap());
-        let y3 = Some(FieldElement::new(77, prime).unwrap());
+        let x3 = Some(FieldElement::from_i64(18, prime).unwrap());
+        let y3 = Some(FieldElement::from_i64(77, prime).unwrap());
 
-        let x4 = Some(FieldElement::new(5, prime).unwrap());
-        let y4 = Some(FieldElement::new(7, prime).unwrap());
+        let x4 = Some(FieldElement::from_i64(5, prime).unwrap());
+        let y4 = Some(FieldElement::from_i64(7, prime).unwrap());
 
         let p1 = Point::new(x1, y1, a, b);
         let p2 = Point::new(x2, y2, a, b);
@@ -174,27 +298,27 @@ mod tests {
     #[test]
     fn addition() {
         let prime = 223;
-        let a = FieldElement::new(5, prime).unwrap();
-        let b = FieldElement::new(7, prime).unwrap();
+        let a = FieldElement::from_i64(5, prime).unwrap();
+        let b = FieldElement::from_i64(7, prime).unwrap();
 
         // p1.x != p2.x
 
-        let x1 = Some(FieldElement::new(2, prime).unwrap());
-        let y1 = Some(FieldElement::new(5, prime).unwrap());
+        let x1 = Some(FieldElement::from_i64(2, prime).unwrap());
+        let y1 = Some(FieldElement::from_i64(5, prime).unwrap());
 
-        let x2 = Some(FieldElement::new(-1, prime).unwrap());
-        let y2 = Some(FieldElement::new(-1, prime).unwrap());
+        let x2 = Some(FieldElement::from_i64(-1, prime).unwrap());
+        let y2 = Some(FieldElement::from_i64(-1, prime).unwrap());
 
         let p1 = Point::new(x1, y1, a, b).unwrap();
         let p2 = Point::new(x2, y2, a, b).unwrap();
         assert_eq!(format!("{}", (p1 + p2).unwrap()), "(3, 216, 5, 7)");
 
         // p1 == p2
-        let x1 = Some(FieldElement::new(-1, prime).unwrap());
-        let y1 = Some(FieldElement::new(-1, prime).unwrap());
+        let x1 = Some(FieldElement::from_i64(-1, prime).unwrap());
+        let y1 = Some(FieldElement::from_i64(-1, prime).unwrap());
 
-        let x2 = Some(FieldElement::new(-1, prime).unwrap());
-        let y2 = Some(FieldElement::new(-1, prime).unwrap());
+        let x2 = Some(FieldElement::from_i64(-1, prime).unwrap());
+        let y2 = Some(FieldElement::from_i64(-1, prime).unwrap());
 
         let p1 = Point::new(x1, y1, a, b).unwrap();
         let p2 = Point::new(x2, y2, a, b).unwrap();
@@ -204,15 +328,15 @@ mod tests {
     #[test]
     fn test_on_curve() {
         let prime = 223;
-        let a = FieldElement::new(0, prime).unwrap();
-        let b = FieldElement::new(7, prime).unwrap();
+        let a = FieldElement::from_i64(0, prime).unwrap();
+        let b = FieldElement::from_i64(7, prime).unwrap();
 
         let valid_points = vec![(192, 105), (17, 56), (1, 193)];
         let invalid_points = vec![(200, 119), (42, 99)];
 
         for (x, y) in valid_points {
-            let x = FieldElement::new(x, prime).unwrap();
-            let y = FieldElement::new(y, prime).unwrap();
+            let x = FieldElement::from_i64(x, prime).unwrap();
+            let y = FieldElement::from_i64(y, prime).unwrap();
 
             let p = Point::new(Some(x), Some(y), a, b);
 
@@ -220,8 +344,8 @@ mod tests {
         }
 
         for (x, y) in invalid_points {
-            let x = FieldElement::new(x, prime).unwrap();
-            let y = FieldElement::new(y, prime).unwrap();
+            let x = FieldElement::from_i64(x, prime).unwrap();
+            let y = FieldElement::from_i64(y, prime).unwrap();
 
             let p = Point::new(Some(x), Some(y), a, b);
             assert!(p.is_err());
@@ -232,43 +356,43 @@ mod tests {
     fn point_addition_over_finite_field() {
         let prime = 223;
 
-        let a = FieldElement::new(0, prime).unwrap();
-        let b = FieldElement::new(7, prime).unwrap();
+        let a = FieldElement::from_i64(0, prime).unwrap();
+        let b = FieldElement::from_i64(7, prime).unwrap();
 
-        let x1 = FieldElement::new(192, prime).unwrap();
-        let y1 = FieldElement::new(105, prime).unwrap();
-        let x2 = FieldElement::new(17, prime).unwrap();
-        let y2 = FieldElement::new(56, prime).unwrap();
+        let x1 = FieldElement::from_i64(192, prime).unwrap();
+        let y1 = FieldElement::from_i64(105, prime).unwrap();
+        let x2 = FieldElement::from_i64(17, prime).unwrap();
+        let y2 = FieldElement::from_i64(56, prime).unwrap();
 
         let p1 = Point::new(Some(x1), Some(y1), a, b).unwrap();
         let p2 = Point::new(Some(x2), Some(y2), a, b).unwrap();
 
         assert_eq!(format!("{}", (p1 + p2).unwrap()), "(170, 142, 0, 7)");
 
-        let x1 = FieldElement::new(170, prime).unwrap();
-        let y1 = FieldElement::new(142, prime).unwrap();
-        let x2 = FieldElement::new(60, prime).unwrap();
-        let y2 = FieldElement::new(139, prime).unwrap();
+        let x1 = FieldElement::from_i64(170, prime).unwrap();
+        let y1 = FieldElement::from_i64(142, prime).unwrap();
+        let x2 = FieldElement::from_i64(60, prime).unwrap();
+        let y2 = FieldElement::from_i64(139, prime).unwrap();
 
         let p1 = Point::new(Some(x1), Some(y1), a, b).unwrap();
         let p2 = Point::new(Some(x2), Some(y2), a, b).unwrap();
 
         assert_eq!(format!("{}", (p1 + p2).unwrap()), "(220, 181, 0, 7)");
 
-        let x1 = FieldElement::new(47, prime).unwrap();
-        let y1 = FieldElement::new(71, prime).unwrap();
-        let x2 = FieldElement::new(17, prime).unwrap();
-        let y2 = FieldElement::new(56, prime).unwrap();
+        let x1 = FieldElement::from_i64(47, prime).unwrap();
+        let y1 = FieldElement::from_i64(71, prime).unwrap();
+        let x2 = FieldElement::from_i64(17, prime).unwrap();
+        let y2 = FieldElement::from_i64(56, prime).unwrap();
 
         let p1 = Point::new(Some(x1), Some(y1), a, b).unwrap();
         let p2 = Point::new(Some(x2), Some(y2), a, b).unwrap();
 
         assert_eq!(format!("{}", (p1 + p2).unwrap()), "(215, 68, 0, 7)");
 
-        let x1 = FieldElement::new(143, prime).unwrap();
-        let y1 = FieldElement::new(98, prime).unwrap();
-        let x2 = FieldElement::new(76, prime).unwrap();
-        let y2 = FieldElement::new(66, prime).unwrap();
+        let x1 = FieldElement::from_i64(143, prime).unwrap();
+        let y1 = FieldElement::from_i64(98, prime).unwrap();
+        let x2 = FieldElement::from_i64(76, prime).unwrap();
+        let y2 = FieldElement::from_i64(66, prime).unwrap();
 
         let p1 = Point::new(Some(x1), Some(y1), a, b).unwrap();
         let p2 = Point::new(Some(x2), Some(y2), a, b).unwrap();
@@ -279,11 +403,11 @@ mod tests {
     #[test]
     fn scalar_multiplication() {
         let prime = 223;
-        let a = FieldElement::new(0, prime).unwrap();
-        let b = FieldElement::new(7, prime).unwrap();
+        let a = FieldElement::from_i64(0, prime).unwrap();
+        let b = FieldElement::from_i64(7, prime).unwrap();
 
-        let x1 = FieldElement::new(47, prime).unwrap();
-        let y1 = FieldElement::new(71, prime).unwrap();
+        let x1 = FieldElement::from_i64(47, prime).unwrap();
+        let y1 = FieldElement::from_i64(71, prime).unwrap();
 
         let p = Point::new(Some(x1), Some(y1), a, b).unwrap();
 
@@ -291,4 +415,78 @@ mod tests {
         assert_eq!(format!("{}", (p * 9).unwrap()), "(69, 86, 0, 7)");
         assert_eq!(format!("{}", (p * 20).unwrap()), "(47, 152, 0, 7)");
     }
+
+    #[test]
+    #[allow(clippy::op_ref)]
+    fn negation_and_subtraction() {
+        let prime = 223;
+        let a = FieldElement::from_i64(0, prime).unwrap();
+        let b = FieldElement::from_i64(7, prime).unwrap();
+
+        let x1 = FieldElement::from_i64(47, prime).unwrap();
+        let y1 = FieldElement::from_i64(71, prime).unwrap();
+
+        let p = Point::new(Some(x1), Some(y1), a, b).unwrap();
+        let infinity = Point::new(None, None, a, b).unwrap();
+        assert_eq!(format!("{}", infinity), "(infinity, 0, 7)");
+        assert!(Point::new(Some(x1), None, a, b).is_err());
+        assert!(Point::new(None, Some(y1), a, b).is_err());
+
+        // The group has order 21, so -P = 20P
+        assert_eq!(-p, (p * 20).unwrap());
+        assert_eq!(-infinity, infinity);
+        assert_eq!((p - p).unwrap(), infinity);
+        assert_eq!(
+            ((p * 9).unwrap() - (p * 2).unwrap()).unwrap(),
+            (p * 7).unwrap()
+        );
+        assert_eq!((infinity - p).unwrap(), -p);
+
+        let minus_one = FieldElement::from_i64(-1, prime).unwrap();
+        let five = FieldElement::from_i64(5, prime).unwrap();
+        assert_eq!((&p - &p).unwrap(), infinity);
+        assert_eq!((&p + &p).unwrap(), (&p * U256::from(2u64)).unwrap());
+
+        let other_curve = Point::new(Some(minus_one), Some(minus_one), five, b).unwrap();
+        assert!((p - other_curve).is_err());
+    }
+
+    #[test]
+    fn secp256k1_scalar_multiplication() {
+        let points = [
+            (
+                U256::from(7u64),
+                "5CBDF0646E5DB4EAA398F365F2EA7A0E3D419B7E0330E39CE92BDDEDCAC4F9BC",
+                "6AEBCA40BA255960A3178D6D861A54DBA813D0B813FDE7B5A5082628087264DA",
+            ),
+            (
+                U256::from(1485u64),
+                "C982196A7466FBBBB0E27A940B6AF926C1A74D5AD07128C82824A11B5398AFDA",
+                "7A91F9EAE64438AFB9CE6448A1C133DB2D8FB9254E4546B6F001637D50901F55",
+            ),
+            (
+                U256::ONE << 128,
+                "8F68B9D2F63B5F339239C1AD981F162EE88C5678723EA3351B7B444C9EC4C0DA",
+                "662A9F2DBA063986DE1D90C2B6BE215DBBEA2CFE95510BFDF23CBF79501FFF82",
+            ),
+            (
+                (U256::ONE << 240) + (U256::ONE << 31),
+                "9577FF57C8234558F293DF502CA4F09CBC65A6572C842B39B366F21717945116",
+                "10B49C67FA9365AD7B90DAB070BE339A1DAF9052373EC30FFAE4F72D5E66D053",
+            ),
+        ];
+
+        for (secret, x, y) in points {
+            let x = FieldElement::new(U256::from_be_hex(x), P).unwrap();
+            let y = FieldElement::new(U256::from_be_hex(y), P).unwrap();
+
+            assert_eq!(
+                (*G * secret).unwrap(),
+                Point::new(Some(x), Some(y), A, B).unwrap()
+            );
+        }
+
+        let infinity = Point::new(None, None, A, B).unwrap();
+        assert_eq!((*G * N).unwrap(), infinity);
+    }
 }
@@ -0,0 +1,346 @@
+use std::fmt;
+
+use rand_core::{CryptoRng, OsRng, RngCore};
+
+use crate::ecc::s256_point::S256Point;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::secp256k1::N;
+use crate::ecc::signature::Signature;
+use crate::ecc::uint::U256;
+use crate::ecc::zeroize::Zeroize;
+use crate::error::Error;
+use crate::hash::hmac::Hmac;
+use crate::hash::sha256::Sha256;
+
+pub struct PrivateKey {
+    secret: Scalar,
+    point: S256Point,
+}
+
+impl PrivateKey {
+    pub fn new(secret: Scalar) -> Result<Self, Error> {
+        if secret.is_zero() {
+            return Err(Error::InvalidInput("Private key can't be zero".to_string()));
+        }
+
+        Ok(Self {
+            secret,
+            point: S256Point::mul_generator(&secret),
+        })
+    }
+
+    // Draws 32 bytes from the operating system until they form a valid key.
+    // Rejection is astronomically rare since N is close to 2^256.
+    pub fn generate() -> Self {
+        Self::generate_with(&mut OsRng)
+    }
+
+    // Same as generate with the caller's randomness source, for targets
+    // without an operating system RNG
+    pub fn generate_with<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut bytes = [0u8; 32];
+
+        loop {
+            rng.fill_bytes(&mut bytes);
+            let mut candidate = U256::from_be_bytes(&bytes);
+
+            if !candidate.is_zero() && candidate < N {
+                bytes.zeroize();
+                let key = Self::new(Scalar::new(candidate)).expect("candidate is non-zero");
+                candidate.zeroize();
+                return key;
+            }
+        }
+    }
+
+    pub fn public_key(&self) -> S256Point {
+        self.point
+    }
+
+    pub(crate) fn secret(&self) -> Scalar {
+        self.secret
+    }
+
+    pub fn sign(&self, z: Scalar) -> Signature {
+        self.sign_recoverable(z).0
+    }
+
+    // Also returns the recovery id: bit 0 is the parity of R.y and bit 1 is set
+    // when R.x overflowed N while being reduced to r.
+    pub fn sign_recoverable(&self, z: Scalar) -> (Signature, u8) {
+        self.sign_recoverable_with_k(z, self.deterministic_k(z, &[]))
+    }
+
+    // RFC 6979 section 3.6 additional data, appended after the message hash
+    // like libsecp256k1 does. The signature is still valid and deterministic
+    // for a given extra, but no longer only depends on the key and message.
+    pub fn sign_with_entropy(&self, z: Scalar, extra: &[u8; 32]) -> Signature {
+        self.sign_recoverable_with_entropy(z, extra).0
+    }
+
+    pub fn sign_recoverable_with_entropy(&self, z: Scalar, extra: &[u8; 32]) -> (Signature, u8) {
+        self.sign_recoverable_with_k(z, self.deterministic_k(z, extra))
+    }
+
+    fn sign_recoverable_with_k(&self, z: Scalar, mut k: Scalar) -> (Signature, u8) {
+        let big_r = S256Point::mul_generator(&k);
+        let (x, y) = match (big_r.x(), big_r.y()) {
+            (Some(x), Some(y)) => (x, y),
+            _ => unreachable!("k is in [1, N), so kG is never infinity"),
+        };
+
+        let r = Scalar::new(x.num());
+        let mut recid = if y.is_even() { 0 } else { 1 };
+        if x.num() >= N {
+            recid |= 2;
+        }
+
+        let mut k_inv = k.invert();
+        let s = (z + r * self.secret) * k_inv;
+        k_inv.zeroize();
+        k.zeroize();
+        let sig = Signature::new(r, s);
+
+        // The low-s form corresponds to signing with -k, whose R has the
+        // opposite y parity
+        if !sig.is_low_s() {
+            recid ^= 1;
+        }
+
+        (sig.normalize_s(), recid)
+    }
+
+    // SHA256 of the compressed shared point, which is libsecp256k1's default
+    // ECDH hash: the x coordinate prefixed with its y parity byte. Fails for
+    // the point at infinity rather than hashing a meaningless secret.
+    pub fn ecdh(&self, public_key: &S256Point) -> Result<[u8; 32], Error> {
+        if public_key.is_infinity() {
+            return Err(Error::PointAtInfinity);
+        }
+
+        let shared = public_key.mul_ct(&self.secret);
+        let mut sec = shared.sec_compressed()?;
+        let hash = Sha256::digest(&sec);
+        sec.zeroize();
+
+        Ok(hash)
+    }
+
+    // RFC 6979 section 3.2 with HMAC-SHA256
+    fn deterministic_k(&self, z: Scalar, extra: &[u8]) -> Scalar {
+        let mut k = [0u8; 32];
+        let mut v = [1u8; 32];
+
+        let mut secret_bytes = self.secret.to_be_bytes();
+        let z_bytes = z.to_be_bytes();
+
+        k = hmac_sha256(&k, &[&v, &[0x00], &secret_bytes, &z_bytes, extra]);
+        v = hmac_sha256(&k, &[&v]);
+        k = hmac_sha256(&k, &[&v, &[0x01], &secret_bytes, &z_bytes, extra]);
+        v = hmac_sha256(&k, &[&v]);
+
+        loop {
+            v = hmac_sha256(&k, &[&v]);
+            let candidate = U256::from_be_bytes(&v);
+
+            if !candidate.is_zero() && candidate < N {
+                k.zeroize();
+                v.zeroize();
+                secret_bytes.zeroize();
+                return Scalar::new(candidate);
+            }
+
+            k = hmac_sha256(&k, &[&v, &[0x00]]);
+            v = hmac_sha256(&k, &[&v]);
+        }
+    }
+}
+
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrivateKey")
+            .field("secret", &"<redacted>")
+            .field("public_key", &self.point)
+            .finish()
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[&[u8]]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new(key);
+
+    for part in data {
+        mac.update(part);
+    }
+
+    mac.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_core::{CryptoRng, RngCore};
+
+    use super::PrivateKey;
+    use crate::ecc::s256_point::S256Point;
+    use crate::ecc::scalar::Scalar;
+    use crate::ecc::uint::U256;
+    use crate::hash::sha256::Sha256;
+
+    fn message_hash(message: &str) -> Scalar {
+        let digest = Sha256::digest(message.as_bytes());
+        Scalar::new(U256::from_be_bytes(&digest))
+    }
+
+    #[test]
+    fn rfc6979_vectors() {
+        let vectors = [
+            (
+                U256::ONE,
+                "Satoshi Nakamoto",
+                "8F8A276C19F4149656B280621E358CCE24F5F52542772691EE69063B74F15D15",
+                "934B1EA10A4B3C1757E2B0C017D0B6143CE3C9A7E6A4A49860D7A6AB210EE3D8",
+                "2442CE9D2B916064108014783E923EC36B49743E2FFA1C4496F01A512AAFD9E5",
+            ),
+            (
+                U256::ONE,
+                "All those moments will be lost in time, like tears in rain. Time to die...",
+                "38AA22D72376B4DBC472E06C3BA403EE0A394DA63FC58D88686C611ABA98D6B3",
+                "8600DBD41E348FE5C9465AB92D23E3DB8B98B873BEECD930736488696438CB6B",
+                "547FE64427496DB33BF66019DACBF0039C04199ABB0122918601DB38A72CFC21",
+            ),
+        ];
+
+        for (secret, message, k, r, s) in vectors {
+            let key = PrivateKey::new(Scalar::new(secret)).unwrap();
+            let z = message_hash(message);
+
+            assert_eq!(key.deterministic_k(z, &[]).num(), U256::from_be_hex(k));
+
+            let sig = key.sign(z);
+            assert_eq!(sig.r.num(), U256::from_be_hex(r));
+            assert_eq!(sig.s.num(), U256::from_be_hex(s));
+        }
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let key = PrivateKey::new(Scalar::new(U256::from_be_hex("12345DEADBEEF"))).unwrap();
+        let z = message_hash("Programming Bitcoin!");
+        let sig = key.sign(z);
+
+        assert!(key.public_key().verify(z, &sig));
+        assert!(sig.is_low_s());
+        assert_eq!(key.sign(z), sig);
+    }
+
+    #[test]
+    fn zero_key() {
+        assert!(PrivateKey::new(Scalar::ZERO).is_err());
+    }
+
+    #[test]
+    fn generate() {
+        let a = PrivateKey::generate();
+        let b = PrivateKey::generate();
+
+        assert_ne!(a.public_key(), b.public_key());
+
+        let z = message_hash("generated keys sign too");
+        assert!(a.public_key().verify(z, &a.sign(z)));
+    }
+
+    // Deterministic stand-in for a caller-supplied RNG: SHA256 of a counter
+    struct CounterRng(u64);
+
+    impl RngCore for CounterRng {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut bytes = [0u8; 8];
+            self.fill_bytes(&mut bytes);
+            u64::from_le_bytes(bytes)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for chunk in dest.chunks_mut(32) {
+                self.0 += 1;
+                let block = Sha256::digest(&self.0.to_le_bytes());
+                chunk.copy_from_slice(&block[..chunk.len()]);
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for CounterRng {}
+
+    #[test]
+    fn generate_with() {
+        let a = PrivateKey::generate_with(&mut CounterRng(0));
+        let b = PrivateKey::generate_with(&mut CounterRng(0));
+        let c = PrivateKey::generate_with(&mut CounterRng(1));
+
+        assert_eq!(a.public_key(), b.public_key());
+        assert_ne!(a.public_key(), c.public_key());
+    }
+
+    #[test]
+    fn sign_with_entropy() {
+        let key = PrivateKey::new(Scalar::from_u64(12345)).unwrap();
+        let z = message_hash("extra entropy");
+
+        let plain = key.sign(z);
+        let a = key.sign_with_entropy(z, &[1; 32]);
+        let b = key.sign_with_entropy(z, &[2; 32]);
+
+        assert!(key.public_key().verify(z, &a));
+        assert!(key.public_key().verify(z, &b));
+        assert_ne!(a, plain);
+        assert_ne!(a, b);
+        assert_eq!(a, key.sign_with_entropy(z, &[1; 32]));
+
+        let (sig, recid) = key.sign_recoverable_with_entropy(z, &[3; 32]);
+        assert_eq!(sig.recover(z, recid).unwrap(), key.public_key());
+    }
+
+    #[test]
+    fn ecdh() {
+        let alice = PrivateKey::new(Scalar::from_u64(0xa11ce)).unwrap();
+        let bob = PrivateKey::new(Scalar::from_u64(0xb0b)).unwrap();
+
+        let shared = alice.ecdh(&bob.public_key()).unwrap();
+        assert_eq!(shared, bob.ecdh(&alice.public_key()).unwrap());
+        assert_ne!(shared, alice.ecdh(&alice.public_key()).unwrap());
+
+        // With a secret of 1 the shared point is G itself
+        let one = PrivateKey::new(Scalar::ONE).unwrap();
+        let shared = one.ecdh(&S256Point::generator()).unwrap();
+        assert_eq!(
+            U256::from_be_bytes(&shared),
+            U256::from_be_hex("0f715baf5d4c2ed329785cef29e562f73488c8a2bb9dbc5700b361d54b9b0554")
+        );
+
+        assert!(alice.ecdh(&S256Point::infinity()).is_err());
+    }
+
+    #[test]
+    fn debug_hides_secret() {
+        let secret = U256::from_be_hex("C0FFEE1234567890");
+        let key = PrivateKey::new(Scalar::new(secret)).unwrap();
+        let debug = format!("{:?}", key);
+
+        assert!(debug.contains("<redacted>"));
+        assert!(!debug.to_lowercase().contains("c0ffee"));
+        assert!(!debug.contains(&format!("{}", secret)));
+    }
+}
@@ -0,0 +1,264 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::sync::LazyLock;
+
+use crate::ecc::field_element::FieldElement;
+use crate::ecc::montgomery::Montgomery;
+use crate::ecc::secp256k1::P;
+use crate::ecc::uint::U256;
+use crate::error::Error;
+
+static MONTGOMERY: LazyLock<Montgomery> = LazyLock::new(|| Montgomery::new(P).expect("P is odd"));
+
+// 2^256 mod P, which is 1 in Montgomery form
+const R: U256 = U256::from_be_hex("1000003D1");
+
+// Element of the secp256k1 base field. The prime is fixed, so none of the
+// operations can fail and they return Self instead of a Result. Like Scalar,
+// the value is kept in Montgomery form; num() converts it back.
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
+pub struct S256Field(U256);
+
+impl S256Field {
+    pub const ZERO: Self = Self(U256::ZERO);
+    pub const ONE: Self = Self(R);
+
+    pub fn new(num: U256) -> Self {
+        Self(MONTGOMERY.encode(&num))
+    }
+
+    pub fn from_u64(num: u64) -> Self {
+        Self::new(U256::from(num))
+    }
+
+    pub fn from_be_bytes(bytes: &[u8; 32]) -> Result<Self, Error> {
+        let num = U256::from_be_bytes(bytes);
+        if num >= P {
+            return Err(Error::InvalidEncoding(
+                "Field element is not below the field prime".to_string(),
+            ));
+        }
+
+        Ok(Self::new(num))
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        self.num().to_be_bytes()
+    }
+
+    pub fn num(&self) -> U256 {
+        MONTGOMERY.decode(&self.0)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn is_even(&self) -> bool {
+        !self.num().is_odd()
+    }
+
+    pub(crate) fn conditional_swap(a: &mut Self, b: &mut Self, choice: bool) {
+        U256::conditional_swap(&mut a.0, &mut b.0, choice);
+    }
+
+    pub fn pow(&self, exponent: &U256) -> Self {
+        Self(MONTGOMERY.pow(&self.0, exponent))
+    }
+
+    // Zero has no inverse and maps to zero
+    pub fn inverse(&self) -> Self {
+        Self::new(self.num().inv_mod(&P).unwrap_or(U256::ZERO))
+    }
+
+    // P = 3 mod 4, so a square root of v is v^((P+1)/4) when one exists.
+    pub fn sqrt(&self) -> Option<Self> {
+        let exponent = (P + U256::ONE) >> 2;
+        let root = self.pow(&exponent);
+
+        if root * root == *self {
+            Some(root)
+        } else {
+            None
+        }
+    }
+}
+
+impl Add for S256Field {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self(self.0.add_mod(&other.0, &P))
+    }
+}
+
+impl Sub for S256Field {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self(self.0.sub_mod(&other.0, &P))
+    }
+}
+
+impl Mul for S256Field {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        Self(MONTGOMERY.mul(&self.0, &other.0))
+    }
+}
+
+impl Div for S256Field {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self::Output {
+        self * other.inverse()
+    }
+}
+
+impl Neg for S256Field {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::ZERO - self
+    }
+}
+
+impl AddAssign for S256Field {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for S256Field {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for S256Field {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl From<S256Field> for FieldElement {
+    fn from(element: S256Field) -> Self {
+        FieldElement {
+            num: element.num(),
+            prime: P,
+        }
+    }
+}
+
+impl TryFrom<FieldElement> for S256Field {
+    type Error = Error;
+
+    fn try_from(element: FieldElement) -> Result<Self, Self::Error> {
+        if element.prime != P {
+            return Err(Error::FieldMismatch);
+        }
+
+        Ok(Self::new(element.num))
+    }
+}
+
+impl fmt::Debug for S256Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "S256Field({:x})", self.num())
+    }
+}
+
+impl fmt::Display for S256Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:x}", self.num())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::S256Field;
+    use crate::ecc::field_element::FieldElement;
+    use crate::ecc::secp256k1::{GX, GY, P};
+    use crate::ecc::uint::U256;
+
+    #[test]
+    fn arithmetic_matches_field_element() {
+        let (a, b) = (GX, GY);
+
+        let (fa, fb) = (S256Field::new(a), S256Field::new(b));
+        let (ga, gb) = (
+            FieldElement::new(a, P).unwrap(),
+            FieldElement::new(b, P).unwrap(),
+        );
+
+        assert_eq!(FieldElement::from(fa + fb), (ga + gb).unwrap());
+        assert_eq!(FieldElement::from(fa - fb), (ga - gb).unwrap());
+        assert_eq!(FieldElement::from(fb - fa), (gb - ga).unwrap());
+        assert_eq!(FieldElement::from(fa * fb), (ga * gb).unwrap());
+        assert_eq!(FieldElement::from(fa / fb), (ga / gb).unwrap());
+    }
+
+    #[test]
+    fn reduction_edge_cases() {
+        let minus_one = S256Field::new(P - U256::ONE);
+
+        assert_eq!(S256Field::ONE.num(), U256::ONE);
+        assert_eq!(minus_one.num(), P - U256::ONE);
+
+        assert_eq!(minus_one * minus_one, S256Field::ONE);
+        assert_eq!(minus_one + S256Field::ONE, S256Field::ZERO);
+        assert_eq!(-S256Field::ONE, minus_one);
+        assert_eq!(S256Field::new(P), S256Field::ZERO);
+        assert_eq!(
+            S256Field::from_u64(7) * S256Field::from_u64(7).inverse(),
+            S256Field::ONE
+        );
+    }
+
+    #[test]
+    fn assign_operators() {
+        let mut a = S256Field::from_u64(5);
+        a += S256Field::from_u64(3);
+        a *= S256Field::from_u64(4);
+        a -= S256Field::ONE;
+
+        assert_eq!(a, S256Field::from_u64(31));
+    }
+
+    #[test]
+    fn sqrt() {
+        // y^2 = x^3 + 7 for the generator point
+        let x = S256Field::new(GX);
+        let y = S256Field::new(GY);
+        let alpha = x.pow(&U256::from(3u64)) + S256Field::from_u64(7);
+        let root = alpha.sqrt().unwrap();
+
+        assert!(root == y || root == -y);
+        assert_eq!(S256Field::ZERO.sqrt(), Some(S256Field::ZERO));
+
+        // -1 is not a square when P = 3 mod 4
+        assert_eq!((-S256Field::ONE).sqrt(), None);
+    }
+
+    #[test]
+    fn bytes() {
+        let x = S256Field::new(GX);
+        assert_eq!(S256Field::from_be_bytes(&x.to_be_bytes()).unwrap(), x);
+        assert!(S256Field::from_be_bytes(&P.to_be_bytes()).is_err());
+        assert!(S256Field::from_be_bytes(&[0xff; 32]).is_err());
+    }
+
+    #[test]
+    fn conversion() {
+        let other = FieldElement::from_i64(5, 223).unwrap();
+        assert!(S256Field::try_from(other).is_err());
+
+        let element = S256Field::from_u64(5);
+        assert_eq!(
+            S256Field::try_from(FieldElement::from(element)).unwrap(),
+            element
+        );
+    }
+}
@@ -0,0 +1,637 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::ecc::constant_time::ConstantTimeEq;
+use crate::ecc::generator_table;
+use crate::ecc::jacobian::JacobianPoint;
+use crate::ecc::point::Point;
+use crate::ecc::s256_field::S256Field;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::secp256k1::{A, B, G, N};
+use crate::ecc::signature::Signature;
+use crate::ecc::uint::U256;
+use crate::error::Error;
+
+// Point on secp256k1. Both operands of every operation are on the same curve,
+// so Add and Mul return S256Point directly instead of a Result.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct S256Point(Point);
+
+impl S256Point {
+    pub fn new(x: S256Field, y: S256Field) -> Result<Self, Error> {
+        let point = Point::affine(x.into(), y.into(), A, B)?;
+        Ok(Self(point))
+    }
+
+    pub fn infinity() -> Self {
+        Self(Point::infinity(A, B))
+    }
+
+    // Only for coordinates produced by arithmetic on valid points
+    pub(crate) fn from_affine_unchecked(x: S256Field, y: S256Field) -> Self {
+        Self(Point::new_unchecked(x.into(), y.into(), A, B))
+    }
+
+    pub fn generator() -> Self {
+        Self(*G)
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.0.is_infinity()
+    }
+
+    pub fn x(&self) -> Option<S256Field> {
+        self.0.x().map(|x| S256Field::new(x.num))
+    }
+
+    pub fn y(&self) -> Option<S256Field> {
+        self.0.y().map(|y| S256Field::new(y.num))
+    }
+
+    // SEC1 compressed encoding: 0x02/0x03 (even/odd y) || x. The point at
+    // infinity has no encoding as a public key.
+    pub fn sec_compressed(&self) -> Result<[u8; 33], Error> {
+        let (x, y) = self.coordinates()?;
+
+        let mut result = [0u8; 33];
+        result[0] = if y.is_even() { 0x02 } else { 0x03 };
+        result[1..].copy_from_slice(&x.to_be_bytes());
+
+        Ok(result)
+    }
+
+    // SEC1 uncompressed encoding: 0x04 || x || y
+    pub fn sec_uncompressed(&self) -> Result<[u8; 65], Error> {
+        let (x, y) = self.coordinates()?;
+
+        let mut result = [0u8; 65];
+        result[0] = 0x04;
+        result[1..33].copy_from_slice(&x.to_be_bytes());
+        result[33..].copy_from_slice(&y.to_be_bytes());
+
+        Ok(result)
+    }
+
+    fn coordinates(&self) -> Result<(S256Field, S256Field), Error> {
+        match (self.x(), self.y()) {
+            (Some(x), Some(y)) => Ok((x, y)),
+            _ => Err(Error::PointAtInfinity),
+        }
+    }
+
+    // Compressed SEC with 33 zero bytes for infinity, as BIP327 encodes
+    // aggregate nonces. Only for hash inputs and formats that define it.
+    pub(crate) fn sec_or_zero(&self) -> [u8; 33] {
+        self.sec_compressed().unwrap_or([0u8; 33])
+    }
+
+    pub fn parse(sec: &[u8]) -> Result<Self, Error> {
+        match sec {
+            [0x04, rest @ ..] if rest.len() == 64 => {
+                let x = parse_coordinate(&rest[..32])?;
+                let y = parse_coordinate(&rest[32..])?;
+
+                Self::new(x, y)
+            }
+            [prefix @ (0x02 | 0x03), rest @ ..] if rest.len() == 32 => {
+                let x = parse_coordinate(rest)?;
+
+                // y^2 = x^3 + 7, sqrt gives one root and P - root is the other
+                let alpha = x * x * x + S256Field::from_u64(7);
+                let beta = alpha.sqrt().ok_or(Error::NotOnCurve)?;
+
+                let want_even = *prefix == 0x02;
+                let y = if beta.is_even() == want_even {
+                    beta
+                } else {
+                    -beta
+                };
+
+                Self::new(x, y)
+            }
+            _ => Err(Error::InvalidEncoding(format!(
+                "Invalid SEC encoding of {} bytes",
+                sec.len()
+            ))),
+        }
+    }
+
+    // ECDSA: with u = z/s and v = r/s, uG + vP must have x coordinate r
+    pub fn verify(&self, z: Scalar, sig: &Signature) -> bool {
+        // With P at infinity uG + vP is just uG, which anyone can compute
+        if self.is_infinity() || sig.r.is_zero() || sig.s.is_zero() {
+            return false;
+        }
+
+        let s_inv = sig.s.invert();
+        let u = z * s_inv;
+        let v = sig.r * s_inv;
+        let total = Self::mul_generator(&u) + self.mul_vartime(&v);
+
+        match total.x() {
+            Some(x) => Scalar::new(x.num()).ct_eq(&sig.r),
+            None => false,
+        }
+    }
+
+    // k*G from a precomputed table, much faster than mul_ct and without its
+    // timing caveats at the start of the ladder
+    pub fn mul_generator(k: &Scalar) -> Self {
+        generator_table::mul_generator(k)
+    }
+
+    // Montgomery ladder for secret scalars. Every bit costs one addition and
+    // one doubling, and the bit only decides a masked swap, so the sequence of
+    // operations is the same for every scalar. The field arithmetic and the
+    // infinity checks at the start of the ladder still branch, so this narrows
+    // the timing side channel rather than closing it completely.
+    pub fn mul_ct(&self, scalar: &Scalar) -> Self {
+        let k = scalar.num();
+        let mut r0 = JacobianPoint::INFINITY;
+        let mut r1 = JacobianPoint::from_affine(self);
+
+        for i in (0..256).rev() {
+            let bit = k.bit(i);
+
+            JacobianPoint::conditional_swap(&mut r0, &mut r1, bit);
+            r1 = r0.add(&r1);
+            r0 = r0.double();
+            JacobianPoint::conditional_swap(&mut r0, &mut r1, bit);
+        }
+
+        r0.to_affine()
+    }
+
+    // Plain double-and-add, which skips the addition for zero bits and so
+    // leaks the scalar through timing. Only for public scalars such as the
+    // ones in signature verification; the * operators use mul_ct.
+    pub fn mul_vartime(&self, scalar: &Scalar) -> Self {
+        let k = scalar.num();
+        let mut result = JacobianPoint::INFINITY;
+
+        for i in (0..k.bits()).rev() {
+            result = result.double();
+
+            if k.bit(i) {
+                result = result.add_affine(self);
+            }
+        }
+
+        result.to_affine()
+    }
+
+    // sum(k_i * P_i) with Pippenger's bucket method. The scalars are cut into
+    // c-bit windows; within a window every point is added once to the bucket
+    // for its digit, and the buckets are combined with two running sums. That
+    // costs about 256/c * (n + 2^c) additions instead of 128 per term.
+    pub fn multi_mul(terms: &[(Scalar, S256Point)]) -> Self {
+        if terms.is_empty() {
+            return Self::infinity();
+        }
+
+        let c = (terms.len().ilog2() / 2 + 2).min(12);
+        let windows = 256u32.div_ceil(c);
+        let mut result = JacobianPoint::INFINITY;
+
+        for w in (0..windows).rev() {
+            for _ in 0..c {
+                result = result.double();
+            }
+
+            let mut buckets = vec![JacobianPoint::INFINITY; (1 << c) - 1];
+            for (k, point) in terms {
+                let digit = window_digit(&k.num(), w * c, c);
+                if digit != 0 {
+                    buckets[digit - 1] = buckets[digit - 1].add_affine(point);
+                }
+            }
+
+            // sum(d * B_d) as B_top + (B_top + B_top-1) + ...
+            let mut running = JacobianPoint::INFINITY;
+            let mut window_sum = JacobianPoint::INFINITY;
+            for bucket in buckets.iter().rev() {
+                running = running.add(bucket);
+                window_sum = window_sum.add(&running);
+            }
+
+            result = result.add(&window_sum);
+        }
+
+        result.to_affine()
+    }
+}
+
+// Bits [start, start + width) of k, width being at most 64
+fn window_digit(k: &U256, start: u32, width: u32) -> usize {
+    let limb = (start / 64) as usize;
+    let shift = start % 64;
+    let mut bits = k.0[limb] >> shift;
+
+    if shift + width > 64 && limb < 3 {
+        bits |= k.0[limb + 1] << (64 - shift);
+    }
+
+    (bits & ((1u64 << width) - 1)) as usize
+}
+
+fn parse_coordinate(bytes: &[u8]) -> Result<S256Field, Error> {
+    S256Field::from_be_bytes(bytes.try_into().expect("coordinates are 32 bytes")).map_err(|_| {
+        Error::InvalidEncoding("SEC coordinate is not below the field prime".to_string())
+    })
+}
+
+impl Add for S256Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        JacobianPoint::from_affine(&self)
+            .add_affine(&other)
+            .to_affine()
+    }
+}
+
+impl Neg for S256Point {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        match (self.x(), self.y()) {
+            (Some(x), Some(y)) => Self::from_affine_unchecked(x, -y),
+            _ => self,
+        }
+    }
+}
+
+impl Sub for S256Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self + -other
+    }
+}
+
+// The operators can't tell secret scalars from public ones, so they all go
+// through the ladder
+impl Mul<U256> for S256Point {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, coef: U256) -> Self::Output {
+        // N * P is the point at infinity, so the coefficient can be reduced first
+        self.mul_ct(&Scalar::new(coef % N))
+    }
+}
+
+impl Mul<u64> for S256Point {
+    type Output = Self;
+
+    fn mul(self, coef: u64) -> Self::Output {
+        self * U256::from(coef)
+    }
+}
+
+impl Mul<Scalar> for S256Point {
+    type Output = Self;
+
+    fn mul(self, coef: Scalar) -> Self::Output {
+        self.mul_ct(&coef)
+    }
+}
+
+impl AddAssign for S256Point {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for S256Point {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign<Scalar> for S256Point {
+    fn mul_assign(&mut self, other: Scalar) {
+        *self = *self * other;
+    }
+}
+
+impl From<S256Point> for Point {
+    fn from(point: S256Point) -> Self {
+        point.0
+    }
+}
+
+impl TryFrom<Point> for S256Point {
+    type Error = Error;
+
+    fn try_from(point: Point) -> Result<Self, Self::Error> {
+        if point.a() != A || point.b() != B {
+            return Err(Error::CurveMismatch);
+        }
+
+        Ok(Self(point))
+    }
+}
+
+impl fmt::Display for S256Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.x(), self.y()) {
+            (Some(x), Some(y)) => write!(f, "S256Point({}, {})", x, y),
+            _ => write!(f, "S256Point(infinity)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{window_digit, S256Point};
+    use crate::ecc::field_element::FieldElement;
+    use crate::ecc::point::Point;
+    use crate::ecc::s256_field::S256Field;
+    use crate::ecc::scalar::Scalar;
+    use crate::ecc::secp256k1::{GX, GY, N};
+    use crate::ecc::signature::Signature;
+    use crate::ecc::uint::U256;
+    use crate::error::Error;
+
+    #[test]
+    fn generator() {
+        let g = S256Point::generator();
+
+        assert_eq!(g.x(), Some(S256Field::new(GX)));
+        assert_eq!(g.y(), Some(S256Field::new(GY)));
+        assert_eq!(
+            S256Point::new(S256Field::new(GX), S256Field::new(GY)).unwrap(),
+            g
+        );
+        assert!(S256Point::new(S256Field::new(GX), S256Field::new(GX)).is_err());
+    }
+
+    #[test]
+    fn operators() {
+        let g = S256Point::generator();
+
+        assert_eq!(g + g, g * 2);
+        assert_eq!(g * 3 + g * 4, g * 7);
+        assert_eq!(g + S256Point::infinity(), g);
+        assert!((g * N).is_infinity());
+        assert_eq!(g * (N + U256::ONE), g);
+        assert_eq!(-g, g * (N - U256::ONE));
+        assert_eq!(g * 7 - g * 3, g * 4);
+        assert!((g - g).is_infinity());
+        assert_eq!(-S256Point::infinity(), S256Point::infinity());
+
+        let mut p = g;
+        for _ in 0..4 {
+            p += p;
+        }
+        p -= g;
+        p *= Scalar::from_u64(2);
+        assert_eq!(p, g * 30);
+        assert_eq!(format!("{}", S256Point::infinity()), "S256Point(infinity)");
+    }
+
+    #[test]
+    fn conversion() {
+        let a = FieldElement::from_i64(0, 223).unwrap();
+        let b = FieldElement::from_i64(7, 223).unwrap();
+        let x = FieldElement::from_i64(47, 223).unwrap();
+        let y = FieldElement::from_i64(71, 223).unwrap();
+        let other = Point::new(Some(x), Some(y), a, b).unwrap();
+
+        assert!(S256Point::try_from(other).is_err());
+
+        let g = S256Point::generator();
+        assert_eq!(S256Point::try_from(Point::from(g)).unwrap(), g);
+    }
+
+    #[test]
+    fn verify() {
+        let point = S256Point::new(
+            S256Field::new(U256::from_be_hex(
+                "887387E452B8EACC4ACFDE10D9AAF7F6D9A0F975AABB10D006E4DA568744D06C",
+            )),
+            S256Field::new(U256::from_be_hex(
+                "61DE6D95231CD89026E286DF3B6AE4A894A3378E393E93A0F45B666329A0AE34",
+            )),
+        )
+        .unwrap();
+
+        let signatures = [
+            (
+                "EC208BAA0FC1C19F708A9CA96FDEFF3AC3F230BB4A7BA4AEDE4942AD003C0F60",
+                "AC8D1C87E51D0D441BE8B3DD5B05C8795B48875DFFE00B7FFCFAC23010D3A395",
+                "68342CEFF8935EDEDD102DD876FFD6BA72D6A427A3EDB13D26EB0781CB423C4",
+            ),
+            (
+                "7C076FF316692A3D7EB3C3BB0F8B1488CF72E1AFCD929E29307032997A838A3D",
+                "EFF69EF2B1BD93A66ED5219ADD4FB51E11A840F404876325A1E8FFE0529A2C",
+                "C7207FEE197D27C618AEA621406F6BF5EF6FCA38681D82B2F06FDDBDCE6FEAB6",
+            ),
+        ];
+
+        for (z, r, s) in signatures {
+            let z = Scalar::new(U256::from_be_hex(z));
+            let sig = Signature::new(
+                Scalar::new(U256::from_be_hex(r)),
+                Scalar::new(U256::from_be_hex(s)),
+            );
+
+            assert!(point.verify(z, &sig));
+            assert!(!point.verify(z + Scalar::ONE, &sig));
+        }
+
+        let zero = Signature::new(Scalar::ZERO, Scalar::ZERO);
+        assert!(!point.verify(Scalar::ONE, &zero));
+    }
+
+    #[test]
+    fn verify_rejects_infinity_key() {
+        // r = x(z/s * G) would verify for any s if the key were allowed to be
+        // the point at infinity
+        let z = Scalar::from_u64(0xc0ffee);
+        let s = Scalar::from_u64(12345);
+        let r = S256Point::mul_generator(&(z * s.invert())).x().unwrap();
+        let forged = Signature::new(Scalar::new(r.num()), s);
+
+        assert!(!S256Point::infinity().verify(z, &forged));
+        assert!(!S256Point::generator()
+            .mul_ct(&Scalar::ZERO)
+            .verify(z, &forged));
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn mul_ct() {
+        let g = S256Point::generator();
+        let p = g * 0x1234u64;
+
+        for k in [
+            Scalar::ZERO,
+            Scalar::ONE,
+            Scalar::from_u64(0xdead_beef),
+            -Scalar::from_u64(3),
+        ] {
+            assert_eq!(p.mul_ct(&k), p.mul_vartime(&k));
+            assert_eq!(g.mul_ct(&k), g.mul_vartime(&k));
+            assert_eq!(p * k, p.mul_vartime(&k));
+            assert_eq!(g * k.num(), S256Point::mul_generator(&k));
+        }
+        assert_eq!(
+            S256Point::infinity().mul_ct(&Scalar::ONE),
+            S256Point::infinity()
+        );
+    }
+
+    #[test]
+    fn multi_mul() {
+        let g = S256Point::generator();
+        let p = g * 11u64;
+        let terms = [
+            (Scalar::from_u64(5), g),
+            (Scalar::from_u64(7), p),
+            (-Scalar::ONE, g),
+        ];
+
+        assert_eq!(S256Point::multi_mul(&terms), g * 81u64);
+        assert_eq!(S256Point::multi_mul(&[]), S256Point::infinity());
+
+        // Enough terms for wider windows, with full-size scalars that straddle
+        // limb boundaries
+        let mut terms = Vec::new();
+        let mut expected = S256Point::infinity();
+        let mut k = Scalar::new(U256::from_be_hex(
+            "C0FFEE00DEADBEEF0123456789ABCDEFFEDCBA98765432100F1E2D3C4B5A6978",
+        ));
+        for i in 0..70u64 {
+            let point = g * (i + 2);
+            k = k * k + Scalar::from_u64(i);
+            terms.push((k, point));
+            expected += point * k;
+        }
+        assert_eq!(S256Point::multi_mul(&terms), expected);
+    }
+
+    #[test]
+    fn window_digits() {
+        let k = U256::from_be_hex("F00000000000000A8000000000000001");
+        assert_eq!(window_digit(&k, 0, 4), 1);
+        assert_eq!(window_digit(&k, 60, 8), 0xa8);
+        assert_eq!(window_digit(&k, 124, 12), 0xf);
+    }
+
+    #[test]
+    fn sec() {
+        let uncompressed = [
+            (
+                U256::from(5000u64),
+                "04ffe558e388852f0120e46af2d1b370f85854a8eb0841811ece0e3e03d282d57c315dc72890a4f10a1481c031b03b351b0dc79901ca18a00cf009dbdb157a1d10",
+            ),
+            (
+                U256::from(2018u64).pow_mod(&U256::from(5u64), &N),
+                "04027f3da1918455e03c46f659266a1bb5204e959db7364d2f473bdf8f0a13cc9dff87647fd023c13b4a4994f17691895806e1b40b57f4fd22581a4f46851f3b06",
+            ),
+            (
+                U256::from(0xdeadbeef12345u64),
+                "04d90cd625ee87dd38656dd95cf79f65f60f7273b67d3096e68bd81e4f5342691f842efa762fd59961d0e99803c61edba8b3e3f7dc3a341836f97733aebf987121",
+            ),
+        ];
+
+        for (secret, expected) in uncompressed {
+            let point = S256Point::generator() * secret;
+            assert_eq!(hex(&point.sec_uncompressed().unwrap()), expected);
+        }
+
+        let compressed = [
+            (
+                U256::from(5001u64),
+                "0357a4f368868a8a6d572991e484e664810ff14c05c0fa023275251151fe0e53d1",
+            ),
+            (
+                U256::from(2019u64).pow_mod(&U256::from(5u64), &N),
+                "02933ec2d2b111b92737ec12f1c5d20f3233a0ad21cd8b36d0bca7a0cfa5cb8701",
+            ),
+            (
+                U256::from(0xdeadbeef54321u64),
+                "0296be5b1292f6c856b3c5654e886fc13511462059089cdf9c479623bfcbe77690",
+            ),
+        ];
+
+        for (secret, expected) in compressed {
+            let point = S256Point::generator() * secret;
+            assert_eq!(hex(&point.sec_compressed().unwrap()), expected);
+        }
+
+        assert_eq!(S256Point::infinity().sec_or_zero(), [0u8; 33]);
+        assert_eq!(
+            S256Point::generator().sec_or_zero(),
+            S256Point::generator().sec_compressed().unwrap()
+        );
+    }
+
+    #[test]
+    fn sec_infinity() {
+        assert_eq!(
+            S256Point::infinity().sec_compressed(),
+            Err(Error::PointAtInfinity)
+        );
+        assert_eq!(
+            S256Point::infinity().sec_uncompressed(),
+            Err(Error::PointAtInfinity)
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let secrets = [
+            U256::from(5001u64),
+            U256::from(0xdeadbeef54321u64),
+            U256::from(2019u64).pow_mod(&U256::from(5u64), &N),
+            N - U256::ONE,
+        ];
+
+        for secret in secrets {
+            let point = S256Point::generator() * secret;
+
+            assert_eq!(
+                S256Point::parse(&point.sec_compressed().unwrap()).unwrap(),
+                point
+            );
+            assert_eq!(
+                S256Point::parse(&point.sec_uncompressed().unwrap()).unwrap(),
+                point
+            );
+        }
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let sec = S256Point::generator().sec_compressed().unwrap();
+
+        // The point at infinity has no SEC encoding, and accepting it as a
+        // public key would let anyone forge signatures for it
+        assert!(S256Point::parse(&[0x00]).is_err());
+
+        // wrong prefix, truncated, and an uncompressed length with a compressed prefix
+        let mut bad_prefix = sec;
+        bad_prefix[0] = 0x05;
+        assert!(S256Point::parse(&bad_prefix).is_err());
+        assert!(S256Point::parse(&sec[..32]).is_err());
+        assert!(
+            S256Point::parse(&S256Point::generator().sec_uncompressed().unwrap()[1..]).is_err()
+        );
+
+        // x = 5 has no matching y on secp256k1
+        let mut off_curve = vec![0x02];
+        off_curve.extend_from_slice(&U256::from(5u64).to_be_bytes());
+        assert!(S256Point::parse(&off_curve).is_err());
+
+        // uncompressed point with y flipped off the curve
+        let mut uncompressed = S256Point::generator().sec_uncompressed().unwrap();
+        uncompressed[64] ^= 1;
+        assert!(S256Point::parse(&uncompressed).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::sync::LazyLock;
+
+use crate::ecc::constant_time::ConstantTimeEq;
+use crate::ecc::montgomery::Montgomery;
+use crate::ecc::secp256k1::N;
+use crate::ecc::uint::U256;
+use crate::ecc::zeroize::Zeroize;
+use crate::error::Error;
+
+static MONTGOMERY: LazyLock<Montgomery> = LazyLock::new(|| Montgomery::new(N).expect("N is odd"));
+
+// 2^256 mod N, which is 1 in Montgomery form
+const R: U256 = U256::from_be_hex("14551231950B75FC4402DA1732FC9BEBF");
+
+// Integer modulo the group order N. Private keys, nonces and signature values
+// live here, while point coordinates live in S256Field (mod P). The value is
+// kept in Montgomery form; num() converts it back.
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Scalar(U256);
+
+impl Scalar {
+    pub const ZERO: Self = Self(U256::ZERO);
+    pub const ONE: Self = Self(R);
+
+    pub fn new(num: U256) -> Self {
+        Self(MONTGOMERY.encode(&num))
+    }
+
+    pub fn from_u64(num: u64) -> Self {
+        Self::new(U256::from(num))
+    }
+
+    pub fn from_be_bytes(bytes: &[u8; 32]) -> Result<Self, Error> {
+        let num = U256::from_be_bytes(bytes);
+        if num >= N {
+            return Err(Error::InvalidEncoding(
+                "Scalar is not below the group order".to_string(),
+            ));
+        }
+
+        Ok(Self::new(num))
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        self.num().to_be_bytes()
+    }
+
+    pub fn num(&self) -> U256 {
+        MONTGOMERY.decode(&self.0)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn pow(&self, exponent: &U256) -> Self {
+        Self(MONTGOMERY.pow(&self.0, exponent))
+    }
+
+    // N is prime, so 1/a = a^(N-2). Zero has no inverse and maps to zero.
+    pub fn invert(&self) -> Self {
+        self.pow(&(N - U256::from(2u64)))
+    }
+}
+
+impl Add for Scalar {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self(self.0.add_mod(&other.0, &N))
+    }
+}
+
+impl Sub for Scalar {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self(self.0.sub_mod(&other.0, &N))
+    }
+}
+
+impl Mul for Scalar {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        Self(MONTGOMERY.mul(&self.0, &other.0))
+    }
+}
+
+impl Neg for Scalar {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::ZERO - self
+    }
+}
+
+impl AddAssign for Scalar {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Scalar {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for Scalar {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl ConstantTimeEq for Scalar {
+    fn ct_eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl From<u64> for Scalar {
+    fn from(num: u64) -> Self {
+        Self::from_u64(num)
+    }
+}
+
+// Scalars are often secrets (keys, nonces, shares) and end up in derived Debug
+// output of the structs holding them, so the value is never printed here
+impl fmt::Debug for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Scalar(<redacted>)")
+    }
+}
+
+impl fmt::Display for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:x}", self.num())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Scalar, MONTGOMERY, R};
+    use crate::ecc::s256_point::S256Point;
+    use crate::ecc::secp256k1::N;
+    use crate::ecc::uint::U256;
+
+    #[test]
+    fn reduction() {
+        assert_eq!(Scalar::new(N), Scalar::ZERO);
+        assert_eq!(Scalar::new(N + U256::from(5u64)), Scalar::from_u64(5));
+        assert_eq!(-Scalar::ONE, Scalar::new(N - U256::ONE));
+        assert_eq!(
+            Scalar::new(N - U256::ONE) + Scalar::from_u64(2),
+            Scalar::ONE
+        );
+    }
+
+    #[test]
+    fn bytes() {
+        let a = -Scalar::from_u64(5);
+        assert_eq!(Scalar::from_be_bytes(&a.to_be_bytes()).unwrap(), a);
+        assert_eq!(Scalar::from_u64(1).to_be_bytes()[31], 1);
+        assert!(Scalar::from_be_bytes(&N.to_be_bytes()).is_err());
+    }
+
+    #[test]
+    fn inverse() {
+        let a = Scalar::new(U256::from_be_hex(
+            "8B2A9C1F4D0E6A7B3C5D9E8F7A6B5C4D3E2F1A0B9C8D7E6F5A4B3C2D1E0F9A8B",
+        ));
+
+        assert_eq!(a * a.invert(), Scalar::ONE);
+        assert_eq!(Scalar::ZERO.invert(), Scalar::ZERO);
+        assert_eq!(
+            (Scalar::from_u64(3) * Scalar::from_u64(5).invert()) * Scalar::from_u64(5),
+            Scalar::from_u64(3)
+        );
+    }
+
+    #[test]
+    fn montgomery_form() {
+        assert_eq!(Scalar::ONE, Scalar::from_u64(1));
+        assert_eq!(MONTGOMERY.encode(&U256::ONE), R);
+        assert_eq!(Scalar::ONE.num(), U256::ONE);
+        assert_eq!(
+            Scalar::from_u64(6) * Scalar::from_u64(7),
+            Scalar::from_u64(42)
+        );
+        assert_eq!(
+            Scalar::from_u64(2).pow(&U256::from(10u64)).num(),
+            U256::from(1024u64)
+        );
+        assert_eq!(format!("{:?}", Scalar::from_u64(255)), "Scalar(<redacted>)");
+    }
+
+    #[test]
+    fn assign_operators() {
+        let mut a = Scalar::from_u64(5);
+        a -= Scalar::from_u64(6);
+        a *= Scalar::from_u64(2);
+        a += Scalar::from_u64(3);
+
+        assert_eq!(a, Scalar::ONE);
+    }
+
+    #[test]
+    fn point_multiplication() {
+        let g = S256Point::generator();
+        let a = Scalar::from_u64(12345);
+        let b = -Scalar::from_u64(345);
+
+        // (a + b)G = aG + bG, even when b wraps around N
+        assert_eq!(g * (a + b), g * a + g * b);
+        assert_eq!(g * (a + b), g * 12000);
+    }
+}
@@ -0,0 +1,335 @@
+use crate::ecc::constant_time::ConstantTimeEq;
+use crate::ecc::private_key::PrivateKey;
+use crate::ecc::s256_field::S256Field;
+use crate::ecc::s256_point::S256Point;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::uint::U256;
+use crate::ecc::x_only_public_key::{Parity, XOnlyPublicKey};
+use crate::ecc::zeroize::Zeroize;
+use crate::error::Error;
+use crate::hash::tagged_hash;
+
+// BIP340 signature: the x coordinate of R (whose y is implicitly even) and s
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SchnorrSignature {
+    pub r: S256Field,
+    pub s: Scalar,
+}
+
+impl SchnorrSignature {
+    pub fn serialize(&self) -> [u8; 64] {
+        let mut result = [0u8; 64];
+        result[..32].copy_from_slice(&self.r.to_be_bytes());
+        result[32..].copy_from_slice(&self.s.to_be_bytes());
+
+        result
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != 64 {
+            return Err(Error::InvalidEncoding(format!(
+                "Schnorr signature is {} bytes, expected 64",
+                bytes.len()
+            )));
+        }
+
+        let r = S256Field::from_be_bytes(bytes[..32].try_into().unwrap()).map_err(|_| {
+            Error::InvalidEncoding("Schnorr signature r is not below the field prime".to_string())
+        })?;
+        let s = Scalar::from_be_bytes(bytes[32..].try_into().unwrap()).map_err(|_| {
+            Error::InvalidEncoding("Schnorr signature s is not below the group order".to_string())
+        })?;
+
+        Ok(Self { r, s })
+    }
+}
+
+pub fn sign(key: &PrivateKey, msg: &[u8], aux_rand: &[u8; 32]) -> SchnorrSignature {
+    let (public_key, parity) =
+        XOnlyPublicKey::from_point(&key.public_key()).expect("private keys are non-zero");
+    let p_bytes = public_key.serialize();
+
+    // Use the secret whose public key has an even y
+    let mut d = match parity {
+        Parity::Even => key.secret(),
+        Parity::Odd => -key.secret(),
+    };
+
+    let mut t = d.to_be_bytes();
+    for (byte, mask) in t.iter_mut().zip(tagged_hash("BIP0340/aux", aux_rand)) {
+        *byte ^= mask;
+    }
+
+    // Sized up front so the masked secret is never left behind by a realloc
+    let mut data = Vec::with_capacity(64 + msg.len());
+    data.extend_from_slice(&t);
+    data.extend_from_slice(&p_bytes);
+    data.extend_from_slice(msg);
+    let mut rand = tagged_hash("BIP0340/nonce", &data);
+    data.zeroize();
+    t.zeroize();
+
+    let mut k0 = Scalar::new(U256::from_be_bytes(&rand));
+    rand.zeroize();
+    assert!(!k0.is_zero(), "nonce is zero with negligible probability");
+
+    let (big_r, parity) = XOnlyPublicKey::from_point(&S256Point::mul_generator(&k0))
+        .expect("k is non-zero, so kG isn't infinity");
+    let mut k = match parity {
+        Parity::Even => k0,
+        Parity::Odd => -k0,
+    };
+    let r_bytes = big_r.serialize();
+
+    let e = challenge(&r_bytes, &p_bytes, msg);
+    let s = k + e * d;
+    k0.zeroize();
+    k.zeroize();
+    d.zeroize();
+
+    SchnorrSignature {
+        r: S256Field::new(U256::from_be_bytes(&r_bytes)),
+        s,
+    }
+}
+
+pub fn verify(public_key: &XOnlyPublicKey, msg: &[u8], sig: &SchnorrSignature) -> bool {
+    let r_bytes = sig.r.to_be_bytes();
+    let e = challenge(&r_bytes, &public_key.serialize(), msg);
+
+    // R = sG - eP
+    let big_r = S256Point::mul_generator(&sig.s) - public_key.to_point().mul_vartime(&e);
+
+    match big_r.x() {
+        Some(x) => has_even_y(&big_r) & x.ct_eq(&sig.r),
+        None => false,
+    }
+}
+
+// Checks every signature at once with a random linear combination:
+// (sum a_i*s_i)G == sum(a_i*R_i) + sum(a_i*e_i*P_i), with a_1 = 1. The a_i
+// are derived from all of the inputs, so they can't be predicted before the
+// batch is fixed and one bad signature makes the whole batch fail.
+pub fn verify_batch(batch: &[(&[u8], XOnlyPublicKey, SchnorrSignature)]) -> bool {
+    let mut inputs = Vec::new();
+    for (msg, public_key, sig) in batch {
+        inputs.extend_from_slice(&public_key.serialize());
+        inputs.extend_from_slice(&sig.serialize());
+        inputs.extend_from_slice(&(msg.len() as u64).to_be_bytes());
+        inputs.extend_from_slice(msg);
+    }
+    let seed = tagged_hash("BIP0340/batch", &inputs);
+
+    let mut s_sum = Scalar::ZERO;
+    let mut terms = Vec::with_capacity(2 * batch.len() + 1);
+
+    for (i, (msg, public_key, sig)) in batch.iter().enumerate() {
+        let a = if i == 0 {
+            Scalar::ONE
+        } else {
+            let data = [&seed[..], &(i as u64).to_be_bytes()].concat();
+            Scalar::new(U256::from_be_bytes(&tagged_hash("BIP0340/batch", &data)))
+        };
+
+        let r_bytes = sig.r.to_be_bytes();
+        let big_r = match XOnlyPublicKey::parse(&r_bytes) {
+            Ok(big_r) => big_r.to_point(),
+            Err(_) => return false,
+        };
+        let e = challenge(&r_bytes, &public_key.serialize(), msg);
+
+        s_sum += a * sig.s;
+        terms.push((a, big_r));
+        terms.push((a * e, public_key.to_point()));
+    }
+
+    // Moving the s term to the right side leaves a single sum that must be infinity
+    terms.push((-s_sum, S256Point::generator()));
+
+    S256Point::multi_mul(&terms).is_infinity()
+}
+
+pub(crate) fn challenge(r_bytes: &[u8], p_bytes: &[u8], msg: &[u8]) -> Scalar {
+    let hash = tagged_hash("BIP0340/challenge", &[r_bytes, p_bytes, msg].concat());
+    Scalar::new(U256::from_be_bytes(&hash))
+}
+
+pub(crate) fn has_even_y(point: &S256Point) -> bool {
+    point.y().map(|y| y.is_even()).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sign, verify, verify_batch, SchnorrSignature};
+    use crate::ecc::private_key::PrivateKey;
+    use crate::ecc::scalar::Scalar;
+    use crate::ecc::uint::U256;
+    use crate::ecc::x_only_public_key::XOnlyPublicKey;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn xonly(s: &str) -> XOnlyPublicKey {
+        XOnlyPublicKey::parse(&unhex(s)).unwrap()
+    }
+
+    // Test vectors 0-3 from BIP340
+    const SIGNING_VECTORS: [(&str, &str, &str, &str, &str); 4] = [
+        (
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA8215\
+             25F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0",
+        ),
+        (
+            "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE3341\
+             8906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+        ),
+        (
+            "C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9",
+            "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8",
+            "C87AA53824B4D7AE2EB035A2B5BBBCCC080E76CDC6D1692C4B0B62D798E6D906",
+            "7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C",
+            "5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1B\
+             AB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7",
+        ),
+        (
+            "0B432B2677937381AEF05BB02A66ECD012773062CF3FA2549E44F58ED2401710",
+            "25D1DFF95105F5253C4022F628A996AD3A0D95FBF21D468A1B33F8C160D8F517",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            "7EB0509757E246F19449885651611CB965ECC1A187DD51B64FDA1EDC9637D5EC\
+             97582B9CB13DB3933705B32BA982AF5AF25FD78881EBB32771FC5922EFC66EA3",
+        ),
+    ];
+
+    #[test]
+    fn bip340_signing() {
+        for (secret, public_key, aux, msg, expected) in SIGNING_VECTORS {
+            let key = PrivateKey::new(Scalar::new(U256::from_be_hex(secret))).unwrap();
+            let public_key = XOnlyPublicKey::parse(&unhex(public_key)).unwrap();
+            let msg = unhex(msg);
+
+            let sig = sign(&key, &msg, &unhex(aux).try_into().unwrap());
+
+            assert_eq!(sig.serialize().to_vec(), unhex(expected));
+            assert!(verify(&public_key, &msg, &sig));
+            assert_eq!(
+                XOnlyPublicKey::from_point(&key.public_key()).unwrap().0,
+                public_key
+            );
+        }
+    }
+
+    #[test]
+    fn bip340_verification() {
+        // Vectors 0-3 are verified in bip340_signing
+        // Vector 4: valid, R.x has many leading zeros
+        let public_key = xonly("D69C3509BB99E412E68B0FE8544E72837DFA30746D8BE2AA65975F29D22DC7B9");
+        let msg = unhex("4DF3C3F68FCC83B27E9D42C90431A72499F17875C81A599B566C9889B9696703");
+        let sig = SchnorrSignature::parse(&unhex(
+            "00000000000000000000003B78CE563F89A0ED9414F5AA28AD0D96D6795F9C63\
+             76AFB1548AF603B3EB45C9F8207DEE1060CB71C04E80F593060B07D28308D7F4",
+        ))
+        .unwrap();
+        assert!(verify(&public_key, &msg, &sig));
+
+        // Vector 5: public key not on the curve, rejected when parsing the key
+        let public_key = "EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34";
+        assert!(XOnlyPublicKey::parse(&unhex(public_key)).is_err());
+
+        let msg = unhex("243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89");
+
+        // Vector 6: R has odd y
+        let public_key = xonly("DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659");
+        let sig = SchnorrSignature::parse(&unhex(
+            "FFF97BD5755EEEA420453A14355235D382F6472F8568A18B2F057A1460297556\
+             3CC27944640AC607CD107AE10923D9EF7A73C643E166BE5EBEAFA34B1AC553E2",
+        ))
+        .unwrap();
+        assert!(!verify(&public_key, &msg, &sig));
+
+        // Vectors 7-11 parse but must not verify
+        for sig in [
+            // Vector 7: negated message
+            "1FA62E331EDBC21C394792D2AB1100A7B432B013DF3F6FF4F99FCB33E0E1515F\
+             28890B3EDB6E7189B630448B515CE4F8622A954CFE545735AAEA5134FCCDB2BD",
+            // Vector 8: negated s
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769\
+             961764B3AA9B2FFCB6EF947B6887A226E8D7C93E00C5ED0C1834FF0D0C2E6DA6",
+            // Vector 9: sG - eP is infinity, with R.x = 0
+            "0000000000000000000000000000000000000000000000000000000000000000\
+             123DDA8328AF9C23A94C1FEECFD123BA4FB73476F0D594DCB65C6425BD186051",
+            // Vector 10: sG - eP is infinity, with R.x = 1
+            "0000000000000000000000000000000000000000000000000000000000000001\
+             7615FBAF5AE28864013C099742DEADB4DBA87F11AC6754F93780D5A1837CF197",
+            // Vector 11: R.x is not on the curve
+            "4A298DACAE57395A15D0795DDBFD1DCB564DA82B0F269BC70A74F8220429BA1D\
+             69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+        ] {
+            let sig = SchnorrSignature::parse(&unhex(sig)).unwrap();
+            assert!(!verify(&public_key, &msg, &sig));
+        }
+
+        // Vectors 12 and 13 are rejected when parsing the signature
+        for sig in [
+            // Vector 12: R.x equals the field prime
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F\
+             69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            // Vector 13: s equals the group order
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769\
+             FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141",
+        ] {
+            assert!(SchnorrSignature::parse(&unhex(sig)).is_err());
+        }
+
+        // Vector 14: public key x exceeds the field prime
+        let public_key = "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30";
+        assert!(XOnlyPublicKey::parse(&unhex(public_key)).is_err());
+    }
+
+    #[test]
+    fn batch_verification() {
+        let messages: [&[u8]; 3] = [b"first", b"second", b"third"];
+        let mut batch = Vec::new();
+
+        for (i, msg) in messages.iter().enumerate() {
+            let key = PrivateKey::new(Scalar::from_u64(1000 + i as u64)).unwrap();
+            let public_key = XOnlyPublicKey::from_point(&key.public_key()).unwrap().0;
+            batch.push((*msg, public_key, sign(&key, msg, &[i as u8; 32])));
+        }
+
+        assert!(verify_batch(&batch));
+        assert!(verify_batch(&[]));
+
+        // Swapping two signatures breaks the batch even though each is valid
+        let mut swapped = batch.clone();
+        swapped[1].2 = batch[2].2;
+        swapped[2].2 = batch[1].2;
+        assert!(!verify_batch(&swapped));
+
+        let mut tampered = batch.clone();
+        tampered[0].0 = b"forged";
+        assert!(!verify_batch(&tampered));
+    }
+
+    #[test]
+    fn parse_rejects_out_of_range() {
+        let mut bytes = [0xFFu8; 64];
+        assert!(SchnorrSignature::parse(&bytes).is_err());
+
+        bytes[..32].copy_from_slice(&[0u8; 32]);
+        assert!(SchnorrSignature::parse(&bytes).is_err());
+        assert!(SchnorrSignature::parse(&bytes[..63]).is_err());
+    }
+}
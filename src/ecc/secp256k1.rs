@@ -0,0 +1,63 @@
+use std::sync::LazyLock;
+
+use crate::ecc::field_element::FieldElement;
+use crate::ecc::point::Point;
+use crate::ecc::uint::U256;
+
+// Field prime 2^256 - 2^32 - 977
+pub const P: U256 =
+    U256::from_be_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F");
+
+// Order of the group generated by G
+pub const N: U256 =
+    U256::from_be_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+
+// y^2 = x^3 + 7
+pub const A: FieldElement = FieldElement {
+    num: U256::ZERO,
+    prime: P,
+};
+pub const B: FieldElement = FieldElement {
+    num: U256::from_u64(7),
+    prime: P,
+};
+
+pub const GX: U256 =
+    U256::from_be_hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798");
+pub const GY: U256 =
+    U256::from_be_hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8");
+
+pub static G: LazyLock<Point> = LazyLock::new(|| {
+    let x = FieldElement::new(GX, P).unwrap();
+    let y = FieldElement::new(GY, P).unwrap();
+
+    Point::new(Some(x), Some(y), A, B).expect("generator is on the curve")
+});
+
+#[cfg(test)]
+mod tests {
+    use super::{A, B, G, GX, GY, N, P};
+    use crate::ecc::field_element::FieldElement;
+    use crate::ecc::point::Point;
+    use crate::ecc::uint::U256;
+
+    #[test]
+    fn generator() {
+        let x = FieldElement::new(GX, P).unwrap();
+        let y = FieldElement::new(GY, P).unwrap();
+
+        assert_eq!(*G, Point::new(Some(x), Some(y), A, B).unwrap());
+        assert_eq!(format!("{}", *G), format!("({}, {}, 0, 7)", GX, GY));
+    }
+
+    #[test]
+    fn parameters() {
+        // 2^256 - 2^32 - 977, written as (2^256 - 1) - (2^32 + 977) + 1
+        let p = U256::MAX - (U256::ONE << 32) - U256::from(977u64) + U256::ONE;
+
+        assert_eq!(p, P);
+        assert!(N < P);
+        assert_eq!(A.prime, P);
+        assert_eq!(B.num, U256::from(7u64));
+    }
+}
@@ -0,0 +1,476 @@
+use std::error;
+use std::fmt;
+
+use crate::ecc::s256_point::S256Point;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::secp256k1::{N, P};
+use crate::ecc::uint::U256;
+use crate::error::Error;
+
+// The BIP66 rule a DER signature broke
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DerError {
+    InvalidLength(usize),
+    MissingSequenceMarker,
+    SequenceLengthMismatch,
+    MissingIntegerMarker,
+    IntegerLengthMismatch,
+    ZeroLengthInteger,
+    NegativeInteger,
+    ExcessivePadding,
+    IntegerOutOfRange,
+}
+
+impl fmt::Display for DerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DerError::InvalidLength(len) => {
+                write!(f, "DER signature is {} bytes, expected 8 to 72", len)
+            }
+            DerError::MissingSequenceMarker => write!(f, "DER signature doesn't start with 0x30"),
+            DerError::SequenceLengthMismatch => {
+                write!(f, "DER sequence length doesn't match the signature size")
+            }
+            DerError::MissingIntegerMarker => write!(f, "DER integer doesn't start with 0x02"),
+            DerError::IntegerLengthMismatch => {
+                write!(f, "DER integer lengths don't add up to the sequence length")
+            }
+            DerError::ZeroLengthInteger => write!(f, "DER integer has zero length"),
+            DerError::NegativeInteger => write!(f, "DER integer is negative"),
+            DerError::ExcessivePadding => write!(f, "DER integer has unnecessary leading zeros"),
+            DerError::IntegerOutOfRange => write!(f, "DER integer is not below the group order"),
+        }
+    }
+}
+
+impl error::Error for DerError {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Signature {
+    pub r: Scalar,
+    pub s: Scalar,
+}
+
+impl Signature {
+    pub fn new(r: Scalar, s: Scalar) -> Self {
+        Self { r, s }
+    }
+
+    // 0x30 [total-len] 0x02 [r-len] [r] 0x02 [s-len] [s]
+    pub fn der(&self) -> Vec<u8> {
+        let r = der_integer(&self.r);
+        let s = der_integer(&self.s);
+
+        let mut result = Vec::with_capacity(2 + r.len() + s.len());
+        result.push(0x30);
+        result.push((r.len() + s.len()) as u8);
+        result.extend_from_slice(&r);
+        result.extend_from_slice(&s);
+
+        result
+    }
+
+    // BIP62: s must be at most N/2, since (r, N - s) verifies just as well
+    pub fn is_low_s(&self) -> bool {
+        self.s.num() <= N >> 1
+    }
+
+    pub fn normalize_s(&self) -> Self {
+        if self.is_low_s() {
+            *self
+        } else {
+            Self::new(self.r, -self.s)
+        }
+    }
+
+    // Recovers the public key Q = r^-1 (sR - zG), where R is the point whose x
+    // coordinate produced r, picked out by the recovery id.
+    pub fn recover(&self, z: Scalar, recid: u8) -> Result<S256Point, Error> {
+        if recid > 3 {
+            return Err(Error::InvalidInput(format!(
+                "Invalid recovery id {}",
+                recid
+            )));
+        }
+
+        if self.r.is_zero() || self.s.is_zero() {
+            return Err(Error::InvalidInput(
+                "Signature values must be non-zero".to_string(),
+            ));
+        }
+
+        let mut x = self.r.num();
+        if recid & 2 != 0 {
+            let (overflowed, carry) = x.overflowing_add(&N);
+            if carry || overflowed >= P {
+                return Err(Error::InvalidInput(
+                    "Recovery id points past the field prime".to_string(),
+                ));
+            }
+            x = overflowed;
+        }
+
+        let mut sec = vec![0x02 | (recid & 1)];
+        sec.extend_from_slice(&x.to_be_bytes());
+        let big_r = S256Point::parse(&sec)?;
+
+        let r_inv = self.r.invert();
+        let q = big_r.mul_vartime(&(self.s * r_inv)) + S256Point::mul_generator(&(-z * r_inv));
+
+        if q.is_infinity() {
+            return Err(Error::PointAtInfinity);
+        }
+
+        Ok(q)
+    }
+
+    // Message-signing format: a header byte 27 + recid (+ 4 for a compressed
+    // key) followed by 32-byte r and s.
+    pub fn serialize_compact(&self, recid: u8, compressed: bool) -> Result<[u8; 65], Error> {
+        if recid > 3 {
+            return Err(Error::InvalidInput(format!(
+                "Invalid recovery id {}",
+                recid
+            )));
+        }
+
+        let mut result = [0u8; 65];
+        result[0] = 27 + recid + if compressed { 4 } else { 0 };
+        result[1..33].copy_from_slice(&self.r.to_be_bytes());
+        result[33..].copy_from_slice(&self.s.to_be_bytes());
+
+        Ok(result)
+    }
+
+    // Returns the signature, recovery id and compressed-key flag
+    pub fn parse_compact(bytes: &[u8]) -> Result<(Self, u8, bool), Error> {
+        if bytes.len() != 65 {
+            return Err(Error::InvalidEncoding(format!(
+                "Compact signature is {} bytes, expected 65",
+                bytes.len()
+            )));
+        }
+
+        let header = bytes[0];
+        if !(27..=34).contains(&header) {
+            return Err(Error::InvalidEncoding(format!(
+                "Invalid compact signature header {}",
+                header
+            )));
+        }
+
+        let compressed = header >= 31;
+        let recid = (header - 27) & 3;
+
+        let r = U256::from_be_bytes(bytes[1..33].try_into().unwrap());
+        let s = U256::from_be_bytes(bytes[33..].try_into().unwrap());
+
+        if r.is_zero() || s.is_zero() || r >= N || s >= N {
+            return Err(Error::InvalidEncoding(
+                "Compact signature values must be in [1, N)".to_string(),
+            ));
+        }
+
+        Ok((Self::new(Scalar::new(r), Scalar::new(s)), recid, compressed))
+    }
+
+    // Strict DER as required by BIP66 (without the trailing sighash byte)
+    pub fn parse_der(der: &[u8]) -> Result<Self, DerError> {
+        let len = der.len();
+
+        if !(8..=72).contains(&len) {
+            return Err(DerError::InvalidLength(len));
+        }
+
+        if der[0] != 0x30 {
+            return Err(DerError::MissingSequenceMarker);
+        }
+
+        if der[1] as usize != len - 2 {
+            return Err(DerError::SequenceLengthMismatch);
+        }
+
+        let r_len = der[3] as usize;
+        if 5 + r_len >= len {
+            return Err(DerError::IntegerLengthMismatch);
+        }
+
+        let s_len = der[5 + r_len] as usize;
+        if r_len + s_len + 6 != len {
+            return Err(DerError::IntegerLengthMismatch);
+        }
+
+        if der[2] != 0x02 || der[4 + r_len] != 0x02 {
+            return Err(DerError::MissingIntegerMarker);
+        }
+
+        let r = parse_der_integer(&der[4..4 + r_len])?;
+        let s = parse_der_integer(&der[6 + r_len..])?;
+
+        Ok(Self::new(r, s))
+    }
+}
+
+fn parse_der_integer(bytes: &[u8]) -> Result<Scalar, DerError> {
+    if bytes.is_empty() {
+        return Err(DerError::ZeroLengthInteger);
+    }
+
+    if bytes[0] & 0x80 != 0 {
+        return Err(DerError::NegativeInteger);
+    }
+
+    // A leading zero is only allowed when the next byte would look negative
+    if bytes.len() > 1 && bytes[0] == 0x00 && bytes[1] & 0x80 == 0 {
+        return Err(DerError::ExcessivePadding);
+    }
+
+    let bytes = if bytes[0] == 0x00 && bytes.len() > 1 {
+        &bytes[1..]
+    } else {
+        bytes
+    };
+
+    if bytes.len() > 32 {
+        return Err(DerError::IntegerOutOfRange);
+    }
+
+    let mut padded = [0u8; 32];
+    padded[32 - bytes.len()..].copy_from_slice(bytes);
+    let value = U256::from_be_bytes(&padded);
+
+    if value >= N {
+        return Err(DerError::IntegerOutOfRange);
+    }
+
+    Ok(Scalar::new(value))
+}
+
+// Minimal big-endian encoding, with a 0x00 pad when the high bit is set so the
+// integer isn't read as negative.
+fn der_integer(value: &Scalar) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(31);
+    let bytes = &bytes[start..];
+
+    let mut result = vec![0x02];
+
+    if bytes[0] & 0x80 != 0 {
+        result.push(bytes.len() as u8 + 1);
+        result.push(0x00);
+    } else {
+        result.push(bytes.len() as u8);
+    }
+
+    result.extend_from_slice(bytes);
+    result
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Signature({}, {})", self.r, self.s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DerError, Signature};
+    use crate::ecc::private_key::PrivateKey;
+    use crate::ecc::scalar::Scalar;
+    use crate::ecc::secp256k1::N;
+    use crate::ecc::uint::U256;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn signature(r: &str, s: &str) -> Signature {
+        Signature::new(
+            Scalar::new(U256::from_be_hex(r)),
+            Scalar::new(U256::from_be_hex(s)),
+        )
+    }
+
+    #[test]
+    fn der() {
+        let sig = signature(
+            "37206A0610995C58074999CB9767B87AF4C4978DB68C06E8E6E81D282047A7C6",
+            "8CA63759C1157EBEAEC0D03CECCA119FC9A75BF8E6D0FA65C841C8E2738CDAEC",
+        );
+
+        assert_eq!(
+            hex(&sig.der()),
+            "3045022037206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c6\
+             0221008ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec"
+        );
+    }
+
+    #[test]
+    fn der_short_integers() {
+        let sig = signature("7F", "80");
+        assert_eq!(hex(&sig.der()), "300702017f02020080");
+
+        let sig = signature("0", "1");
+        assert_eq!(hex(&sig.der()), "3006020100020101");
+    }
+
+    #[test]
+    fn parse_der_roundtrip() {
+        let sigs = [
+            signature(
+                "37206A0610995C58074999CB9767B87AF4C4978DB68C06E8E6E81D282047A7C6",
+                "8CA63759C1157EBEAEC0D03CECCA119FC9A75BF8E6D0FA65C841C8E2738CDAEC",
+            ),
+            signature("7F", "80"),
+            signature("0", "1"),
+        ];
+
+        for sig in sigs {
+            assert_eq!(Signature::parse_der(&sig.der()), Ok(sig));
+        }
+    }
+
+    #[test]
+    fn parse_der_rejects_non_canonical() {
+        let valid = unhex("300702017f02020080");
+        assert!(Signature::parse_der(&valid).is_ok());
+
+        let cases = [
+            ("3006020101020101", Ok(())),
+            ("30060201010201", Err(DerError::InvalidLength(7))),
+            ("310702017f02020080", Err(DerError::MissingSequenceMarker)),
+            ("300802017f02020080", Err(DerError::SequenceLengthMismatch)),
+            ("300703017f02020080", Err(DerError::MissingIntegerMarker)),
+            ("300702017f03020080", Err(DerError::MissingIntegerMarker)),
+            ("300702037f02020080", Err(DerError::IntegerLengthMismatch)),
+            ("300702017f02030080", Err(DerError::IntegerLengthMismatch)),
+            ("3008020002040000007f", Err(DerError::ZeroLengthInteger)),
+            ("3006020180020101", Err(DerError::NegativeInteger)),
+            ("30070202007f020101", Err(DerError::ExcessivePadding)),
+            ("300802017f0203000080", Err(DerError::ExcessivePadding)),
+            (
+                "300702017f0202008000",
+                Err(DerError::SequenceLengthMismatch),
+            ),
+        ];
+
+        for (der, expected) in cases {
+            assert_eq!(
+                Signature::parse_der(&unhex(der)).map(|_| ()),
+                expected,
+                "{}",
+                der
+            );
+        }
+
+        // r = N is out of range even though the encoding is canonical
+        let mut der = vec![0x30, 0x26, 0x02, 0x21, 0x00];
+        der.extend_from_slice(&N.to_be_bytes());
+        der.extend_from_slice(&[0x02, 0x01, 0x01]);
+        assert_eq!(Signature::parse_der(&der), Err(DerError::IntegerOutOfRange));
+    }
+
+    #[test]
+    fn compact_roundtrip() {
+        let sig = signature(
+            "37206A0610995C58074999CB9767B87AF4C4978DB68C06E8E6E81D282047A7C6",
+            "8CA63759C1157EBEAEC0D03CECCA119FC9A75BF8E6D0FA65C841C8E2738CDAEC",
+        );
+
+        for recid in 0..4 {
+            for compressed in [false, true] {
+                let bytes = sig.serialize_compact(recid, compressed).unwrap();
+
+                assert_eq!(bytes[0], 27 + recid + if compressed { 4 } else { 0 });
+                assert_eq!(
+                    Signature::parse_compact(&bytes),
+                    Ok((sig, recid, compressed))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn compact_invalid() {
+        let sig = signature("1", "2");
+        let bytes = sig.serialize_compact(0, true).unwrap();
+        assert!(sig.serialize_compact(4, true).is_err());
+
+        assert!(Signature::parse_compact(&bytes[..64]).is_err());
+
+        let mut bad_header = bytes;
+        bad_header[0] = 35;
+        assert!(Signature::parse_compact(&bad_header).is_err());
+
+        let mut zero_s = bytes;
+        zero_s[64] = 0;
+        assert!(Signature::parse_compact(&zero_s).is_err());
+
+        let mut big_r = bytes;
+        big_r[1..33].copy_from_slice(&N.to_be_bytes());
+        assert!(Signature::parse_compact(&big_r).is_err());
+    }
+
+    #[test]
+    fn recover() {
+        let key = PrivateKey::new(Scalar::new(U256::from_be_hex("ABCDEF0123456789"))).unwrap();
+        let z = Scalar::new(U256::from_be_hex(
+            "EC208BAA0FC1C19F708A9CA96FDEFF3AC3F230BB4A7BA4AEDE4942AD003C0F60",
+        ));
+        let (sig, recid) = key.sign_recoverable(z);
+
+        assert_eq!(sig.recover(z, recid), Ok(key.public_key()));
+
+        // Through the compact format, as message verification would do it
+        let (parsed, parsed_recid, _) =
+            Signature::parse_compact(&sig.serialize_compact(recid, true).unwrap()).unwrap();
+        assert_eq!(parsed.recover(z, parsed_recid), Ok(key.public_key()));
+
+        // The other parity gives a different, unrelated key
+        let other = sig.recover(z, recid ^ 1).unwrap();
+        assert_ne!(other, key.public_key());
+        assert!(other.verify(z, &sig));
+
+        assert!(sig.recover(z, 4).is_err());
+    }
+
+    #[test]
+    fn low_s() {
+        let high = signature(
+            "37206A0610995C58074999CB9767B87AF4C4978DB68C06E8E6E81D282047A7C6",
+            "8CA63759C1157EBEAEC0D03CECCA119FC9A75BF8E6D0FA65C841C8E2738CDAEC",
+        );
+        let low = high.normalize_s();
+
+        assert!(!high.is_low_s());
+        assert!(low.is_low_s());
+        assert_eq!(low.r, high.r);
+        assert_eq!(low.s, -high.s);
+        assert_eq!(low.normalize_s(), low);
+
+        // N/2 itself is the largest low s
+        let half = Signature::new(Scalar::ONE, Scalar::new(N >> 1));
+        assert!(half.is_low_s());
+        assert!(!Signature::new(Scalar::ONE, Scalar::new((N >> 1) + U256::ONE)).is_low_s());
+    }
+
+    #[test]
+    fn malleated_signature_still_verifies() {
+        let key = PrivateKey::new(Scalar::from_u64(12345)).unwrap();
+        let z = Scalar::from_u64(67890);
+        let sig = key.sign(z);
+        let malleated = Signature::new(sig.r, -sig.s);
+
+        assert!(sig.is_low_s());
+        assert!(!malleated.is_low_s());
+        assert!(key.public_key().verify(z, &malleated));
+        assert_eq!(malleated.normalize_s(), sig);
+    }
+}
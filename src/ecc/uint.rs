@@ -0,0 +1,663 @@
+// Limb loops index several arrays in lockstep, which reads better with ranges.
+#![allow(clippy::needless_range_loop)]
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Rem, Shl, Shr, Sub};
+
+// 256-bit unsigned integer stored as four little-endian 64-bit limbs.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub struct U256(pub(crate) [u64; 4]);
+
+impl U256 {
+    pub const ZERO: Self = Self([0; 4]);
+    pub const ONE: Self = Self([1, 0, 0, 0]);
+    pub const MAX: Self = Self([u64::MAX; 4]);
+
+    pub const fn from_limbs(limbs: [u64; 4]) -> Self {
+        Self(limbs)
+    }
+
+    pub const fn from_u64(n: u64) -> Self {
+        Self([n, 0, 0, 0])
+    }
+
+    // Parses a big-endian hex string of at most 64 digits. Meant for constants,
+    // so it panics instead of returning an error.
+    pub const fn from_be_hex(hex: &str) -> Self {
+        let bytes = hex.as_bytes();
+        assert!(bytes.len() <= 64, "hex string longer than 256 bits");
+
+        let mut limbs = [0u64; 4];
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let digit = match bytes[bytes.len() - 1 - i] {
+                b @ b'0'..=b'9' => b - b'0',
+                b @ b'a'..=b'f' => b - b'a' + 10,
+                b @ b'A'..=b'F' => b - b'A' + 10,
+                _ => panic!("invalid hex digit"),
+            };
+
+            limbs[i / 16] |= (digit as u64) << ((i % 16) * 4);
+            i += 1;
+        }
+
+        Self(limbs)
+    }
+
+    pub fn from_be_bytes(bytes: &[u8; 32]) -> Self {
+        let mut limbs = [0u64; 4];
+
+        for (i, chunk) in bytes.chunks_exact(8).enumerate() {
+            limbs[3 - i] = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+
+        Self(limbs)
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+
+        for (i, chunk) in bytes.chunks_exact_mut(8).enumerate() {
+            chunk.copy_from_slice(&self.0[3 - i].to_be_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    // Swaps a and b when choice is set, doing the same work either way so the
+    // choice doesn't show up in timing.
+    pub(crate) fn conditional_swap(a: &mut Self, b: &mut Self, choice: bool) {
+        let mask = 0u64.wrapping_sub(choice as u64);
+
+        for i in 0..4 {
+            let t = mask & (a.0[i] ^ b.0[i]);
+            a.0[i] ^= t;
+            b.0[i] ^= t;
+        }
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.0[0] & 1 == 1
+    }
+
+    pub fn bit(&self, index: u32) -> bool {
+        index < 256 && (self.0[(index / 64) as usize] >> (index % 64)) & 1 == 1
+    }
+
+    // Number of significant bits, zero for zero.
+    pub fn bits(&self) -> u32 {
+        for i in (0..4).rev() {
+            if self.0[i] != 0 {
+                return 64 * i as u32 + 64 - self.0[i].leading_zeros();
+            }
+        }
+
+        0
+    }
+
+    pub fn overflowing_add(&self, other: &Self) -> (Self, bool) {
+        let mut limbs = [0u64; 4];
+        let mut carry = false;
+
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let (sum, c1) = self.0[i].overflowing_add(other.0[i]);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = c1 || c2;
+        }
+
+        (Self(limbs), carry)
+    }
+
+    pub fn overflowing_sub(&self, other: &Self) -> (Self, bool) {
+        let mut limbs = [0u64; 4];
+        let mut borrow = false;
+
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let (diff, b1) = self.0[i].overflowing_sub(other.0[i]);
+            let (diff, b2) = diff.overflowing_sub(borrow as u64);
+            *limb = diff;
+            borrow = b1 || b2;
+        }
+
+        (Self(limbs), borrow)
+    }
+
+    // Full 512-bit product as eight little-endian limbs.
+    pub fn widening_mul(&self, other: &Self) -> [u64; 8] {
+        let mut result = [0u64; 8];
+
+        for i in 0..4 {
+            let mut carry = 0u128;
+
+            for j in 0..4 {
+                let t = self.0[i] as u128 * other.0[j] as u128 + result[i + j] as u128 + carry;
+                result[i + j] = t as u64;
+                carry = t >> 64;
+            }
+
+            result[i + 4] = carry as u64;
+        }
+
+        result
+    }
+
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let mut wide = [0u64; 8];
+        wide[..4].copy_from_slice(&self.0);
+
+        let (quotient, remainder) = div_rem_wide(&wide, divisor);
+        let mut q = [0u64; 4];
+        q.copy_from_slice(&quotient[..4]);
+
+        (Self(q), remainder)
+    }
+
+    // Reduces a 512-bit little-endian number modulo `modulus`.
+    pub fn reduce_wide(wide: &[u64; 8], modulus: &Self) -> Self {
+        div_rem_wide(wide, modulus).1
+    }
+
+    pub fn add_mod(&self, other: &Self, modulus: &Self) -> Self {
+        let (sum, carry) = self.overflowing_add(other);
+
+        if carry || sum >= *modulus {
+            sum.overflowing_sub(modulus).0
+        } else {
+            sum
+        }
+    }
+
+    pub fn sub_mod(&self, other: &Self, modulus: &Self) -> Self {
+        let (diff, borrow) = self.overflowing_sub(other);
+
+        if borrow {
+            diff.overflowing_add(modulus).0
+        } else {
+            diff
+        }
+    }
+
+    pub fn mul_mod(&self, other: &Self, modulus: &Self) -> Self {
+        Self::reduce_wide(&self.widening_mul(other), modulus)
+    }
+
+    // Binary extended Euclid: keeps u = x1*a and v = x2*a (mod m) while
+    // shrinking u and v with halvings and subtractions until one reaches 1.
+    // Needs an odd modulus; returns None for that or when gcd(a, m) != 1.
+    pub fn inv_mod(&self, modulus: &Self) -> Option<Self> {
+        if !modulus.is_odd() {
+            return None;
+        }
+
+        let mut u = *self % *modulus;
+        let mut v = *modulus;
+        let mut x1 = Self::ONE;
+        let mut x2 = Self::ZERO;
+
+        while u != Self::ONE && v != Self::ONE {
+            if u.is_zero() || v.is_zero() {
+                return None;
+            }
+
+            while !u.is_odd() {
+                u = u >> 1;
+                x1 = x1.half_mod(modulus);
+            }
+            while !v.is_odd() {
+                v = v >> 1;
+                x2 = x2.half_mod(modulus);
+            }
+
+            if u >= v {
+                u = u - v;
+                x1 = x1.sub_mod(&x2, modulus);
+            } else {
+                v = v - u;
+                x2 = x2.sub_mod(&x1, modulus);
+            }
+        }
+
+        Some(if u == Self::ONE { x1 } else { x2 })
+    }
+
+    // x/2 mod an odd m: x + m is even when x is odd, and may carry into bit 256
+    fn half_mod(&self, modulus: &Self) -> Self {
+        if !self.is_odd() {
+            return *self >> 1;
+        }
+
+        let (sum, carry) = self.overflowing_add(modulus);
+        let mut half = sum >> 1;
+        if carry {
+            half.0[3] |= 1 << 63;
+        }
+
+        half
+    }
+
+    pub fn pow_mod(&self, exp: &Self, modulus: &Self) -> Self {
+        let mut result = Self::ONE % *modulus;
+        let base = *self % *modulus;
+
+        for i in (0..exp.bits()).rev() {
+            result = result.mul_mod(&result, modulus);
+
+            if exp.bit(i) {
+                result = result.mul_mod(&base, modulus);
+            }
+        }
+
+        result
+    }
+
+    fn div_rem_u64(&self, divisor: u64) -> (Self, u64) {
+        let mut quotient = [0u64; 4];
+        let mut rem = 0u128;
+
+        for i in (0..4).rev() {
+            let cur = (rem << 64) | self.0[i] as u128;
+            quotient[i] = (cur / divisor as u128) as u64;
+            rem = cur % divisor as u128;
+        }
+
+        (Self(quotient), rem as u64)
+    }
+}
+
+// Knuth's Algorithm D (TAOCP 4.3.1) with 64-bit digits.
+fn div_rem_wide(numerator: &[u64; 8], divisor: &U256) -> ([u64; 8], U256) {
+    let n = match divisor.0.iter().rposition(|&limb| limb != 0) {
+        Some(i) => i + 1,
+        None => panic!("attempt to divide by zero"),
+    };
+    let m = match numerator.iter().rposition(|&limb| limb != 0) {
+        Some(i) => i + 1,
+        None => return ([0; 8], U256::ZERO),
+    };
+
+    let mut quotient = [0u64; 8];
+
+    if m < n {
+        let mut remainder = [0u64; 4];
+        remainder[..m].copy_from_slice(&numerator[..m]);
+        return (quotient, U256(remainder));
+    }
+
+    if n == 1 {
+        let d = divisor.0[0] as u128;
+        let mut rem = 0u128;
+
+        for i in (0..m).rev() {
+            let cur = (rem << 64) | numerator[i] as u128;
+            quotient[i] = (cur / d) as u64;
+            rem = cur % d;
+        }
+
+        return (quotient, U256::from_u64(rem as u64));
+    }
+
+    // Normalize so the top divisor limb has its high bit set.
+    let shift = divisor.0[n - 1].leading_zeros();
+    let mut vn = [0u64; 4];
+    let mut un = [0u64; 9];
+
+    for i in (0..n).rev() {
+        vn[i] = divisor.0[i] << shift;
+        if shift > 0 && i > 0 {
+            vn[i] |= divisor.0[i - 1] >> (64 - shift);
+        }
+    }
+
+    un[m] = if shift > 0 {
+        numerator[m - 1] >> (64 - shift)
+    } else {
+        0
+    };
+    for i in (0..m).rev() {
+        un[i] = numerator[i] << shift;
+        if shift > 0 && i > 0 {
+            un[i] |= numerator[i - 1] >> (64 - shift);
+        }
+    }
+
+    const BASE: u128 = 1 << 64;
+
+    for j in (0..=m - n).rev() {
+        let top = ((un[j + n] as u128) << 64) | un[j + n - 1] as u128;
+        let mut qhat = top / vn[n - 1] as u128;
+        let mut rhat = top % vn[n - 1] as u128;
+
+        while qhat >= BASE || qhat * vn[n - 2] as u128 > ((rhat << 64) | un[j + n - 2] as u128) {
+            qhat -= 1;
+            rhat += vn[n - 1] as u128;
+            if rhat >= BASE {
+                break;
+            }
+        }
+
+        // Multiply and subtract qhat * vn from the current window of un.
+        let mut borrow: i128 = 0;
+        for i in 0..n {
+            let p = qhat * vn[i] as u128;
+            let t = un[i + j] as i128 - borrow - (p as u64) as i128;
+            un[i + j] = t as u64;
+            borrow = (p >> 64) as i128 - (t >> 64);
+        }
+        let t = un[j + n] as i128 - borrow;
+        un[j + n] = t as u64;
+
+        if t < 0 {
+            // qhat was one too large, add the divisor back.
+            qhat -= 1;
+            let mut carry = 0u128;
+            for i in 0..n {
+                let sum = un[i + j] as u128 + vn[i] as u128 + carry;
+                un[i + j] = sum as u64;
+                carry = sum >> 64;
+            }
+            un[j + n] = un[j + n].wrapping_add(carry as u64);
+        }
+
+        quotient[j] = qhat as u64;
+    }
+
+    let mut remainder = [0u64; 4];
+    for i in 0..n {
+        remainder[i] = un[i] >> shift;
+        if shift > 0 {
+            remainder[i] |= un[i + 1] << (64 - shift);
+        }
+    }
+
+    (quotient, U256(remainder))
+}
+
+impl From<u64> for U256 {
+    fn from(n: u64) -> Self {
+        Self::from_u64(n)
+    }
+}
+
+impl From<u32> for U256 {
+    fn from(n: u32) -> Self {
+        Self::from_u64(n as u64)
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        for i in (0..4).rev() {
+            match self.0[i].cmp(&other.0[i]) {
+                Ordering::Equal => continue,
+                ordering => return ordering,
+            }
+        }
+
+        Ordering::Equal
+    }
+}
+
+impl Add for U256 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        let (sum, overflow) = self.overflowing_add(&other);
+        assert!(!overflow, "attempt to add with overflow");
+        sum
+    }
+}
+
+impl Sub for U256 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        let (diff, overflow) = self.overflowing_sub(&other);
+        assert!(!overflow, "attempt to subtract with overflow");
+        diff
+    }
+}
+
+impl Mul for U256 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        let wide = self.widening_mul(&other);
+        assert!(wide[4..] == [0; 4], "attempt to multiply with overflow");
+        Self([wide[0], wide[1], wide[2], wide[3]])
+    }
+}
+
+impl Div for U256 {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self::Output {
+        self.div_rem(&other).0
+    }
+}
+
+impl Rem for U256 {
+    type Output = Self;
+
+    fn rem(self, other: Self) -> Self::Output {
+        self.div_rem(&other).1
+    }
+}
+
+impl Shl<u32> for U256 {
+    type Output = Self;
+
+    fn shl(self, shift: u32) -> Self::Output {
+        if shift >= 256 {
+            return Self::ZERO;
+        }
+
+        let limb_shift = (shift / 64) as usize;
+        let bit_shift = shift % 64;
+        let mut limbs = [0u64; 4];
+
+        for i in limb_shift..4 {
+            limbs[i] = self.0[i - limb_shift] << bit_shift;
+            if bit_shift > 0 && i > limb_shift {
+                limbs[i] |= self.0[i - limb_shift - 1] >> (64 - bit_shift);
+            }
+        }
+
+        Self(limbs)
+    }
+}
+
+impl Shr<u32> for U256 {
+    type Output = Self;
+
+    fn shr(self, shift: u32) -> Self::Output {
+        if shift >= 256 {
+            return Self::ZERO;
+        }
+
+        let limb_shift = (shift / 64) as usize;
+        let bit_shift = shift % 64;
+        let mut limbs = [0u64; 4];
+
+        for i in 0..4 - limb_shift {
+            limbs[i] = self.0[i + limb_shift] >> bit_shift;
+            if bit_shift > 0 && i + limb_shift + 1 < 4 {
+                limbs[i] |= self.0[i + limb_shift + 1] << (64 - bit_shift);
+            }
+        }
+
+        Self(limbs)
+    }
+}
+
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Peel off 19 decimal digits at a time, the most that fit in a u64.
+        let mut chunks = Vec::new();
+        let mut n = *self;
+
+        while !n.is_zero() {
+            let (q, r) = n.div_rem_u64(10_000_000_000_000_000_000);
+            chunks.push(r);
+            n = q;
+        }
+
+        write!(f, "{}", chunks.pop().unwrap())?;
+        for chunk in chunks.iter().rev() {
+            write!(f, "{:019}", chunk)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::LowerHex for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:016x}{:016x}{:016x}{:016x}",
+            self.0[3], self.0[2], self.0[1], self.0[0]
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::U256;
+
+    #[test]
+    fn parse_and_display() {
+        let n =
+            U256::from_be_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F");
+
+        assert_eq!(
+            format!("{}", n),
+            "115792089237316195423570985008687907853269984665640564039457584007908834671663"
+        );
+        assert_eq!(
+            format!("{:x}", n),
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"
+        );
+        assert_eq!(format!("{}", U256::ZERO), "0");
+        assert_eq!(format!("{}", U256::from(1234u64)), "1234");
+    }
+
+    #[test]
+    fn bytes() {
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+
+        let n = U256::from_be_bytes(&bytes);
+
+        assert_eq!(
+            n,
+            U256::from_be_hex("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+        );
+        assert_eq!(n.to_be_bytes(), bytes);
+    }
+
+    #[test]
+    fn add_sub() {
+        let a = U256::from_limbs([u64::MAX, u64::MAX, 0, 0]);
+        let b = U256::ONE;
+
+        assert_eq!(a + b, U256::from_limbs([0, 0, 1, 0]));
+        assert_eq!((a + b) - b, a);
+        assert_eq!(U256::MAX.overflowing_add(&U256::ONE), (U256::ZERO, true));
+        assert_eq!(U256::ZERO.overflowing_sub(&U256::ONE), (U256::MAX, true));
+    }
+
+    #[test]
+    fn shifts() {
+        let n = U256::from(0b1011u64);
+
+        assert_eq!(n << 130, U256::from_limbs([0, 0, 0b1011 << 2, 0]));
+        assert_eq!((n << 130) >> 130, n);
+        assert_eq!((n << 255) >> 255, U256::ONE);
+        assert_eq!(n << 256, U256::ZERO);
+        assert_eq!(U256::MAX.bits(), 256);
+        assert_eq!(n.bits(), 4);
+    }
+
+    #[test]
+    fn inv_mod() {
+        let p = U256::from(223u64);
+        for a in 1..223u64 {
+            let inverse = U256::from(a).inv_mod(&p).unwrap();
+            assert_eq!(U256::from(a).mul_mod(&inverse, &p), U256::ONE);
+        }
+
+        // Moduli close to 2^256 exercise the carry in half_mod
+        let m = U256::MAX;
+        let a =
+            U256::from_be_hex("8B2A9C1F4D0E6A7B3C5D9E8F7A6B5C4D3E2F1A0B9C8D7E6F5A4B3C2D1E0F9A8B");
+        let inverse = a.inv_mod(&m).unwrap();
+        assert_eq!(a.mul_mod(&inverse, &m), U256::ONE);
+
+        assert_eq!(U256::ZERO.inv_mod(&p), None);
+        assert_eq!(U256::from(6u64).inv_mod(&U256::from(9u64)), None);
+        assert_eq!(U256::from(3u64).inv_mod(&U256::from(10u64)), None);
+    }
+
+    #[test]
+    fn conditional_swap() {
+        let (mut a, mut b) = (U256::ONE, U256::MAX);
+
+        U256::conditional_swap(&mut a, &mut b, false);
+        assert_eq!((a, b), (U256::ONE, U256::MAX));
+
+        U256::conditional_swap(&mut a, &mut b, true);
+        assert_eq!((a, b), (U256::MAX, U256::ONE));
+    }
+
+    #[test]
+    fn division() {
+        let a = U256::from_be_hex("123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0");
+        let b = U256::from_be_hex("FEDCBA9876543210FEDCBA9");
+
+        let (q, r) = a.div_rem(&b);
+
+        assert!(r < b);
+        assert_eq!(q * b + r, a);
+        assert_eq!(U256::from(100u64) / U256::from(7u64), U256::from(14u64));
+        assert_eq!(U256::from(100u64) % U256::from(7u64), U256::from(2u64));
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        let p =
+            U256::from_be_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F");
+        let a = p - U256::ONE;
+
+        // (-1) * (-1) = 1 and (-1) + (-1) = -2
+        assert_eq!(a.mul_mod(&a, &p), U256::ONE);
+        assert_eq!(a.add_mod(&a, &p), p - U256::from(2u64));
+        assert_eq!(U256::ONE.sub_mod(&U256::from(2u64), &p), a);
+
+        // Fermat: a^(p-1) = 1
+        let x = U256::from_be_hex("DEADBEEF0123456789");
+        assert_eq!(x.pow_mod(&a, &p), U256::ONE);
+        assert_eq!(
+            U256::from(3u64).pow_mod(&U256::from(5u64), &U256::from(7u64)),
+            U256::from(5u64)
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+
+use crate::ecc::s256_point::S256Point;
+use crate::error::Error;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+// BIP340 public key: only the x coordinate is serialized and the point is the
+// one with even y. The lifted point is kept so it isn't recomputed on use.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct XOnlyPublicKey(S256Point);
+
+impl XOnlyPublicKey {
+    // Drops y from a full point, returning the parity it had. An odd point P
+    // maps to the same key as -P.
+    pub fn from_point(point: &S256Point) -> Result<(Self, Parity), Error> {
+        let (x, y) = match (point.x(), point.y()) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return Err(Error::PointAtInfinity),
+        };
+
+        let key = Self::lift_x(&x.to_be_bytes())?;
+        let parity = if y.is_even() {
+            Parity::Even
+        } else {
+            Parity::Odd
+        };
+
+        Ok((key, parity))
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes: &[u8; 32] = bytes.try_into().map_err(|_| {
+            Error::InvalidEncoding(format!("X-only key is {} bytes, expected 32", bytes.len()))
+        })?;
+
+        Self::lift_x(bytes)
+    }
+
+    pub fn serialize(&self) -> [u8; 32] {
+        self.0
+            .x()
+            .expect("x-only keys are never infinity")
+            .num()
+            .to_be_bytes()
+    }
+
+    pub fn to_point(&self) -> S256Point {
+        self.0
+    }
+
+    fn lift_x(x: &[u8; 32]) -> Result<Self, Error> {
+        let mut sec = vec![0x02];
+        sec.extend_from_slice(x);
+
+        Ok(Self(S256Point::parse(&sec)?))
+    }
+}
+
+impl From<XOnlyPublicKey> for S256Point {
+    fn from(key: XOnlyPublicKey) -> Self {
+        key.0
+    }
+}
+
+impl fmt::Display for XOnlyPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.serialize() {
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Parity, XOnlyPublicKey};
+    use crate::ecc::s256_point::S256Point;
+    use crate::ecc::secp256k1::N;
+    use crate::ecc::uint::U256;
+
+    #[test]
+    fn from_point() {
+        let g = S256Point::generator();
+
+        // G has even y, so its x-only key lifts back to G and -G maps to it too
+        let (key, parity) = XOnlyPublicKey::from_point(&g).unwrap();
+        assert_eq!(parity, Parity::Even);
+        assert_eq!(key.to_point(), g);
+
+        let minus_g = g * (N - U256::ONE);
+        let (odd_key, parity) = XOnlyPublicKey::from_point(&minus_g).unwrap();
+        assert_eq!(parity, Parity::Odd);
+        assert_eq!(odd_key, key);
+
+        assert!(XOnlyPublicKey::from_point(&S256Point::infinity()).is_err());
+    }
+
+    #[test]
+    fn serialization() {
+        let point = S256Point::generator() * 3;
+        let (key, _) = XOnlyPublicKey::from_point(&point).unwrap();
+
+        assert_eq!(
+            format!("{}", key),
+            "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"
+        );
+        assert_eq!(XOnlyPublicKey::parse(&key.serialize()).unwrap(), key);
+        assert_eq!(&point.sec_compressed().unwrap()[1..], &key.serialize());
+
+        // x = 5 is not on the curve, and lengths other than 32 are rejected
+        assert!(XOnlyPublicKey::parse(&U256::from(5u64).to_be_bytes()).is_err());
+        assert!(XOnlyPublicKey::parse(&key.serialize()[1..]).is_err());
+    }
+}
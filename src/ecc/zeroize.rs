@@ -0,0 +1,96 @@
+use std::ptr;
+use std::sync::atomic::{compiler_fence, Ordering};
+
+use crate::ecc::uint::U256;
+
+// Overwrites secret material with zeros. The writes are volatile so the
+// compiler can't drop them as dead stores right before the memory is freed.
+pub trait Zeroize {
+    fn zeroize(&mut self);
+}
+
+impl Zeroize for u8 {
+    fn zeroize(&mut self) {
+        // SAFETY: self is a valid, aligned and exclusive reference
+        unsafe { ptr::write_volatile(self, 0) };
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+impl Zeroize for u64 {
+    fn zeroize(&mut self) {
+        // SAFETY: self is a valid, aligned and exclusive reference
+        unsafe { ptr::write_volatile(self, 0) };
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+impl<T: Zeroize, const N: usize> Zeroize for [T; N] {
+    fn zeroize(&mut self) {
+        self.as_mut_slice().zeroize();
+    }
+}
+
+impl<T: Zeroize> Zeroize for [T] {
+    fn zeroize(&mut self) {
+        for item in self.iter_mut() {
+            item.zeroize();
+        }
+    }
+}
+
+// Clears the spare capacity as well, since earlier contents may linger there
+// after a truncate.
+impl Zeroize for Vec<u8> {
+    fn zeroize(&mut self) {
+        self.as_mut_slice().zeroize();
+        self.clear();
+        // SAFETY: each pointer comes from a &mut MaybeUninit<u8> inside the
+        // allocation, and writing a byte to uninitialized memory is sound
+        self.spare_capacity_mut()
+            .iter_mut()
+            .for_each(|byte| unsafe { ptr::write_volatile(byte.as_mut_ptr(), 0) });
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+impl Zeroize for U256 {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Zeroize;
+    use crate::ecc::scalar::Scalar;
+    use crate::ecc::uint::U256;
+
+    #[test]
+    fn arrays_and_integers() {
+        let mut bytes = [0xabu8; 32];
+        bytes.zeroize();
+        assert_eq!(bytes, [0u8; 32]);
+
+        let mut n = U256::MAX;
+        n.zeroize();
+        assert!(n.is_zero());
+
+        let mut k = Scalar::from(12345u64);
+        k.zeroize();
+        assert!(k.is_zero());
+    }
+
+    #[test]
+    fn vec_spare_capacity() {
+        let mut data = vec![0xffu8; 64];
+        data.truncate(16);
+        data.zeroize();
+        assert!(data.is_empty());
+
+        // SAFETY: the capacity is at least 64 and zeroize just initialized
+        // every spare byte
+        unsafe { data.set_len(64) };
+        assert!(data.iter().all(|&byte| byte == 0));
+    }
+}
@@ -0,0 +1,171 @@
+use std::error;
+use std::fmt;
+
+use crate::hash::hash256;
+
+// Bitcoin's alphabet drops 0, O, I and l, which are easy to confuse
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+const CHECKSUM_LEN: usize = 4;
+
+// Why a Base58 or Base58Check string failed to decode
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Base58Error {
+    InvalidCharacter(char),
+    TooShort(usize),
+    InvalidChecksum,
+}
+
+impl fmt::Display for Base58Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Base58Error::InvalidCharacter(c) => write!(f, "{:?} is not a Base58 character", c),
+            Base58Error::TooShort(len) => {
+                write!(
+                    f,
+                    "Base58Check data is {} bytes, shorter than its checksum",
+                    len
+                )
+            }
+            Base58Error::InvalidChecksum => write!(f, "Base58Check checksum doesn't match"),
+        }
+    }
+}
+
+impl error::Error for Base58Error {}
+
+// Treats the bytes as one big-endian number and writes it in base 58, with a
+// '1' for every leading zero byte so they survive the round trip
+pub fn encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|&&b| b == 0).count();
+
+    // Little-endian base-58 digits, each step multiplying the number so far by
+    // 256 and adding the next byte
+    let mut digits: Vec<u8> = Vec::with_capacity(data.len() * 138 / 100 + 1);
+    for &byte in &data[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut result = String::with_capacity(zeros + digits.len());
+    result.extend(std::iter::repeat_n('1', zeros));
+    result.extend(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char));
+
+    result
+}
+
+pub fn decode(s: &str) -> Result<Vec<u8>, Base58Error> {
+    let zeros = s.chars().take_while(|&c| c == '1').count();
+
+    // Little-endian bytes, the reverse of encode
+    let mut bytes: Vec<u8> = Vec::with_capacity(s.len() * 733 / 1000 + 1);
+    for c in s.chars().skip(zeros) {
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a as char == c)
+            .ok_or(Base58Error::InvalidCharacter(c))?;
+
+        let mut carry = value as u32;
+        for byte in bytes.iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut result = vec![0u8; zeros];
+    result.extend(bytes.iter().rev());
+
+    Ok(result)
+}
+
+// Base58 of the data followed by the first four bytes of its hash256
+pub fn encode_check(data: &[u8]) -> String {
+    let mut payload = Vec::with_capacity(data.len() + CHECKSUM_LEN);
+    payload.extend_from_slice(data);
+    payload.extend_from_slice(&hash256(data)[..CHECKSUM_LEN]);
+
+    encode(&payload)
+}
+
+pub fn decode_check(s: &str) -> Result<Vec<u8>, Base58Error> {
+    let mut payload = decode(s)?;
+    if payload.len() < CHECKSUM_LEN {
+        return Err(Base58Error::TooShort(payload.len()));
+    }
+
+    let data_len = payload.len() - CHECKSUM_LEN;
+    if hash256(&payload[..data_len])[..CHECKSUM_LEN] != payload[data_len..] {
+        return Err(Base58Error::InvalidChecksum);
+    }
+    payload.truncate(data_len);
+
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, decode_check, encode, encode_check, Base58Error};
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn roundtrip() {
+        let vectors = [
+            ("", ""),
+            ("00", "1"),
+            ("0000", "11"),
+            ("61", "2g"),
+            ("626262", "a3gV"),
+            ("48656c6c6f20576f726c6421", "2NEpo7TZRRrLZSi2U"),
+            ("0000287fb4cd", "11233QC4"),
+        ];
+
+        for (hex, base58) in vectors {
+            assert_eq!(encode(&unhex(hex)), base58);
+            assert_eq!(decode(base58).unwrap(), unhex(hex));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_characters() {
+        for c in ['0', 'O', 'I', 'l', '+', ' '] {
+            let s = format!("2NEp{}o7", c);
+            assert_eq!(decode(&s), Err(Base58Error::InvalidCharacter(c)));
+        }
+    }
+
+    #[test]
+    fn checked() {
+        // P2PKH address for private key 1
+        let payload = unhex("00751e76e8199196d454941c45d1b3a323f1433bd6");
+        let address = "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH";
+
+        assert_eq!(encode_check(&payload), address);
+        assert_eq!(decode_check(address).unwrap(), payload);
+        assert_eq!(decode_check(&encode_check(&[])).unwrap(), Vec::<u8>::new());
+
+        assert_eq!(
+            decode_check("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMJ"),
+            Err(Base58Error::InvalidChecksum)
+        );
+        assert_eq!(decode_check("2g"), Err(Base58Error::TooShort(1)));
+    }
+}
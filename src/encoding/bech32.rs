@@ -0,0 +1,440 @@
+use std::error;
+use std::fmt;
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+const SEPARATOR: char = '1';
+const CHECKSUM_LEN: usize = 6;
+const MAX_LEN: usize = 90;
+
+// Generator coefficients of the BCH code behind the checksum
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+// Why a bech32 string or segwit address failed to encode or decode
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Bech32Error {
+    InvalidLength(usize),
+    MixedCase,
+    MissingSeparator,
+    InvalidHrp,
+    InvalidCharacter(char),
+    InvalidChecksum,
+    InvalidPadding,
+    HrpMismatch,
+    InvalidWitnessVersion(u8),
+    InvalidProgramLength(usize),
+    WrongVariant,
+}
+
+impl fmt::Display for Bech32Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bech32Error::InvalidLength(len) => {
+                write!(f, "Bech32 string is {} characters, expected 8 to 90", len)
+            }
+            Bech32Error::MixedCase => write!(f, "Bech32 string mixes upper and lower case"),
+            Bech32Error::MissingSeparator => write!(f, "Bech32 string has no '1' separator"),
+            Bech32Error::InvalidHrp => write!(f, "Bech32 human-readable part is invalid"),
+            Bech32Error::InvalidCharacter(c) => write!(f, "{:?} is not a bech32 character", c),
+            Bech32Error::InvalidChecksum => write!(f, "Bech32 checksum doesn't match"),
+            Bech32Error::InvalidPadding => write!(f, "Bech32 data has invalid padding"),
+            Bech32Error::HrpMismatch => write!(f, "Address is for a different network"),
+            Bech32Error::InvalidWitnessVersion(v) => write!(f, "Witness version {} is invalid", v),
+            Bech32Error::InvalidProgramLength(len) => {
+                write!(f, "Witness program is {} bytes, expected 2 to 40", len)
+            }
+            Bech32Error::WrongVariant => {
+                write!(f, "Checksum variant doesn't match the witness version")
+            }
+        }
+    }
+}
+
+impl error::Error for Bech32Error {}
+
+// BIP173 bech32 and its BIP350 fix bech32m differ only in the constant the
+// checksum is xored with. Witness v0 uses bech32 and later versions bech32m.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Variant {
+    fn constant(self) -> u32 {
+        match self {
+            Variant::Bech32 => 1,
+            Variant::Bech32m => 0x2bc830a3,
+        }
+    }
+
+    pub fn for_witness_version(version: u8) -> Self {
+        if version == 0 {
+            Variant::Bech32
+        } else {
+            Variant::Bech32m
+        }
+    }
+}
+
+fn polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    let mut chk: u32 = 1;
+    for value in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ value as u32;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= generator;
+            }
+        }
+    }
+
+    chk
+}
+
+// High bits of each HRP character, a zero, then the low bits
+fn hrp_expand(hrp: &str) -> impl Iterator<Item = u8> + '_ {
+    hrp.bytes()
+        .map(|b| b >> 5)
+        .chain([0])
+        .chain(hrp.bytes().map(|b| b & 31))
+}
+
+fn create_checksum(hrp: &str, data: &[u8], variant: Variant) -> [u8; CHECKSUM_LEN] {
+    let values = hrp_expand(hrp)
+        .chain(data.iter().copied())
+        .chain([0; CHECKSUM_LEN]);
+    let modulus = polymod(values) ^ variant.constant();
+
+    let mut checksum = [0u8; CHECKSUM_LEN];
+    for (i, value) in checksum.iter_mut().enumerate() {
+        *value = ((modulus >> (5 * (5 - i))) & 31) as u8;
+    }
+
+    checksum
+}
+
+fn check_hrp(hrp: &str) -> Result<(), Bech32Error> {
+    if hrp.is_empty() || hrp.len() > 83 || !hrp.bytes().all(|b| (33..=126).contains(&b)) {
+        return Err(Bech32Error::InvalidHrp);
+    }
+
+    Ok(())
+}
+
+// Regroups bits between word sizes, e.g. bytes into the 5-bit values bech32
+// carries. Padding is added when `pad` is set and must be zero and under one
+// input word when it isn't.
+pub fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, Bech32Error> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max = (1u32 << to) - 1;
+    let mut result = Vec::with_capacity((data.len() * from as usize).div_ceil(to as usize));
+
+    for &value in data {
+        if (value as u32) >> from != 0 {
+            return Err(Bech32Error::InvalidPadding);
+        }
+        acc = (acc << from) | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            result.push(((acc >> bits) & max) as u8);
+        }
+    }
+
+    if pad {
+        if bits > 0 {
+            result.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || (acc << (to - bits)) & max != 0 {
+        return Err(Bech32Error::InvalidPadding);
+    }
+
+    Ok(result)
+}
+
+// Encodes 5-bit values under the given HRP. The result is lowercase.
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> Result<String, Bech32Error> {
+    check_hrp(hrp)?;
+    if hrp.bytes().any(|b| b.is_ascii_uppercase()) {
+        return Err(Bech32Error::InvalidHrp);
+    }
+
+    let len = hrp.len() + 1 + data.len() + CHECKSUM_LEN;
+    if len > MAX_LEN {
+        return Err(Bech32Error::InvalidLength(len));
+    }
+    if let Some(&value) = data.iter().find(|&&value| value > 31) {
+        return Err(Bech32Error::InvalidCharacter(value as char));
+    }
+
+    let checksum = create_checksum(hrp, data, variant);
+
+    let mut result = String::with_capacity(len);
+    result.push_str(hrp);
+    result.push(SEPARATOR);
+    result.extend(
+        data.iter()
+            .chain(&checksum)
+            .map(|&value| CHARSET[value as usize] as char),
+    );
+
+    Ok(result)
+}
+
+// Returns the lowercased HRP, the 5-bit values without the checksum, and
+// which variant's checksum matched
+pub fn decode(s: &str) -> Result<(String, Vec<u8>, Variant), Bech32Error> {
+    if s.len() < 8 || s.len() > MAX_LEN {
+        return Err(Bech32Error::InvalidLength(s.len()));
+    }
+    if s.bytes().any(|b| b.is_ascii_lowercase()) && s.bytes().any(|b| b.is_ascii_uppercase()) {
+        return Err(Bech32Error::MixedCase);
+    }
+    let s = s.to_ascii_lowercase();
+
+    let split = s.rfind(SEPARATOR).ok_or(Bech32Error::MissingSeparator)?;
+    let (hrp, rest) = (&s[..split], &s[split + 1..]);
+    check_hrp(hrp)?;
+    if rest.len() < CHECKSUM_LEN {
+        return Err(Bech32Error::InvalidChecksum);
+    }
+
+    let mut data = rest
+        .chars()
+        .map(|c| {
+            CHARSET
+                .iter()
+                .position(|&a| a as char == c)
+                .map(|value| value as u8)
+                .ok_or(Bech32Error::InvalidCharacter(c))
+        })
+        .collect::<Result<Vec<u8>, Bech32Error>>()?;
+
+    let variant = match polymod(hrp_expand(hrp).chain(data.iter().copied())) {
+        c if c == Variant::Bech32.constant() => Variant::Bech32,
+        c if c == Variant::Bech32m.constant() => Variant::Bech32m,
+        _ => return Err(Bech32Error::InvalidChecksum),
+    };
+    data.truncate(data.len() - CHECKSUM_LEN);
+
+    Ok((hrp.to_string(), data, variant))
+}
+
+// Segwit address for a witness program: the version as one 5-bit value, then
+// the program regrouped into 5-bit values, checksummed with the variant the
+// version calls for
+pub fn encode_segwit_address(
+    hrp: &str,
+    version: u8,
+    program: &[u8],
+) -> Result<String, Bech32Error> {
+    check_witness_program(version, program)?;
+
+    let mut data = vec![version];
+    data.extend(convert_bits(program, 8, 5, true)?);
+
+    encode(hrp, &data, Variant::for_witness_version(version))
+}
+
+// Returns the witness version and program of an address for the expected HRP
+pub fn decode_segwit_address(hrp: &str, address: &str) -> Result<(u8, Vec<u8>), Bech32Error> {
+    let (decoded_hrp, data, variant) = decode(address)?;
+    if decoded_hrp != hrp.to_ascii_lowercase() {
+        return Err(Bech32Error::HrpMismatch);
+    }
+
+    let (&version, program) = data
+        .split_first()
+        .ok_or(Bech32Error::InvalidProgramLength(0))?;
+    if variant != Variant::for_witness_version(version) {
+        return Err(Bech32Error::WrongVariant);
+    }
+
+    let program = convert_bits(program, 5, 8, false)?;
+    check_witness_program(version, &program)?;
+
+    Ok((version, program))
+}
+
+fn check_witness_program(version: u8, program: &[u8]) -> Result<(), Bech32Error> {
+    if version > 16 {
+        return Err(Bech32Error::InvalidWitnessVersion(version));
+    }
+    if !(2..=40).contains(&program.len()) {
+        return Err(Bech32Error::InvalidProgramLength(program.len()));
+    }
+    // v0 programs are either a P2WPKH key hash or a P2WSH script hash
+    if version == 0 && program.len() != 20 && program.len() != 32 {
+        return Err(Bech32Error::InvalidProgramLength(program.len()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        convert_bits, decode, decode_segwit_address, encode, encode_segwit_address, Bech32Error,
+        Variant,
+    };
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn valid_checksums() {
+        // From BIP173 and BIP350
+        let vectors = [
+            ("A12UEL5L", Variant::Bech32),
+            ("a12uel5l", Variant::Bech32),
+            (
+                "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+                Variant::Bech32,
+            ),
+            (
+                "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+                Variant::Bech32,
+            ),
+            ("?1ezyfcl", Variant::Bech32),
+            ("A1LQFN3A", Variant::Bech32m),
+            ("a1lqfn3a", Variant::Bech32m),
+            (
+                "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+                Variant::Bech32m,
+            ),
+            (
+                "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+                Variant::Bech32m,
+            ),
+            ("?1v759aa", Variant::Bech32m),
+        ];
+
+        for (s, variant) in vectors {
+            let (hrp, data, decoded_variant) = decode(s).unwrap();
+            assert_eq!(decoded_variant, variant);
+            assert_eq!(
+                encode(&hrp, &data, variant).unwrap(),
+                s.to_ascii_lowercase()
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_strings() {
+        let cases = [
+            ("1nwldj5", Bech32Error::InvalidLength(7)),
+            ("A1G7SGD8", Bech32Error::InvalidChecksum),
+            ("x1b4n0q5v", Bech32Error::InvalidCharacter('b')),
+            ("li1dgmt3", Bech32Error::InvalidChecksum),
+            ("10a06t8", Bech32Error::InvalidLength(7)),
+            ("1qzzfhee", Bech32Error::InvalidHrp),
+            ("pzry9x0s0muk", Bech32Error::MissingSeparator),
+            ("a12UEL5L", Bech32Error::MixedCase),
+            ("\u{7f}1axkwrx", Bech32Error::InvalidHrp),
+        ];
+
+        for (s, err) in cases {
+            assert_eq!(decode(s), Err(err));
+        }
+    }
+
+    #[test]
+    fn segwit_addresses() {
+        let vectors = [
+            (
+                "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4",
+                0,
+                "751e76e8199196d454941c45d1b3a323f1433bd6",
+            ),
+            (
+                "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+                1,
+                "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            ),
+            ("BC1SW50QGDZ25J", 16, "751e"),
+            (
+                "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y",
+                1,
+                "751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6",
+            ),
+        ];
+
+        for (address, version, program) in vectors {
+            assert_eq!(
+                decode_segwit_address("bc", address).unwrap(),
+                (version, unhex(program))
+            );
+            assert_eq!(
+                encode_segwit_address("bc", version, &unhex(program)).unwrap(),
+                address.to_ascii_lowercase()
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_segwit_addresses() {
+        let cases = [
+            // v0 with a bech32m checksum
+            (
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh",
+                Bech32Error::WrongVariant,
+            ),
+            (
+                "tb1q0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq24jc47",
+                Bech32Error::HrpMismatch,
+            ),
+            (
+                "BC1QR508D6QEJXTDG4Y5R3ZARVARYV98GJ9P",
+                Bech32Error::InvalidProgramLength(16),
+            ),
+            ("bc1pw5dgrnzv", Bech32Error::InvalidProgramLength(1)),
+        ];
+
+        for (address, err) in cases {
+            assert_eq!(decode_segwit_address("bc", address), Err(err));
+        }
+
+        // v1 with a bech32 checksum
+        let mut data = vec![1];
+        data.extend(convert_bits(&[0x75; 32], 8, 5, true).unwrap());
+        let address = encode("bc", &data, Variant::Bech32).unwrap();
+        assert_eq!(
+            decode_segwit_address("bc", &address),
+            Err(Bech32Error::WrongVariant)
+        );
+
+        // A whole extra 5-bit word of padding
+        let mut data = vec![2];
+        data.extend(convert_bits(&[0x75; 16], 8, 5, true).unwrap());
+        data.push(0);
+        let address = encode("bc", &data, Variant::Bech32m).unwrap();
+        assert_eq!(
+            decode_segwit_address("bc", &address),
+            Err(Bech32Error::InvalidPadding)
+        );
+
+        assert_eq!(
+            encode_segwit_address("bc", 17, &[0; 20]),
+            Err(Bech32Error::InvalidWitnessVersion(17))
+        );
+    }
+
+    #[test]
+    fn regroups_bits() {
+        let bytes = unhex("751e76e8199196d454941c45d1b3a323f1433bd6");
+        let words = convert_bits(&bytes, 8, 5, true).unwrap();
+
+        assert_eq!(words.len(), 32);
+        assert_eq!(convert_bits(&words, 5, 8, false).unwrap(), bytes);
+        assert_eq!(
+            convert_bits(&[31], 5, 8, false),
+            Err(Bech32Error::InvalidPadding)
+        );
+    }
+}
@@ -0,0 +1,2 @@
+pub mod base58;
+pub mod bech32;
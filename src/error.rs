@@ -0,0 +1,128 @@
+use std::{error, fmt, io};
+
+use crate::ecc::signature::DerError;
+use crate::encoding::base58::Base58Error;
+use crate::encoding::bech32::Bech32Error;
+
+// Error for every fallible API in the crate. Variants that can only come from
+// one kind of bad input carry no data; the rest say what was wrong.
+#[derive(Debug)]
+pub enum Error {
+    // Curve and field arithmetic
+    NotOnCurve,
+    FieldMismatch,
+    CurveMismatch,
+    InvalidModulus,
+    DivisionByZero,
+    PointAtInfinity,
+    // Parsing
+    InvalidEncoding(String),
+    Der(DerError),
+    Base58(Base58Error),
+    Bech32(Bech32Error),
+    Io(io::Error),
+    // Well-formed values that the operation can't accept
+    InvalidInput(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotOnCurve => write!(f, "Point is not on the curve"),
+            Error::FieldMismatch => write!(f, "Field elements are from different fields"),
+            Error::CurveMismatch => write!(f, "Points are on different curves"),
+            Error::InvalidModulus => write!(f, "Modulus can't be zero"),
+            Error::DivisionByZero => write!(f, "Zero has no inverse"),
+            Error::PointAtInfinity => write!(f, "Unexpected point at infinity"),
+            Error::InvalidEncoding(reason) => write!(f, "Invalid encoding: {}", reason),
+            Error::Der(err) => write!(f, "{}", err),
+            Error::Base58(err) => write!(f, "{}", err),
+            Error::Bech32(err) => write!(f, "{}", err),
+            Error::Io(err) => write!(f, "{}", err),
+            Error::InvalidInput(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+// io::Error has no PartialEq, so I/O errors compare by kind
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Error::InvalidEncoding(a), Error::InvalidEncoding(b)) => a == b,
+            (Error::InvalidInput(a), Error::InvalidInput(b)) => a == b,
+            (Error::Der(a), Error::Der(b)) => a == b,
+            (Error::Base58(a), Error::Base58(b)) => a == b,
+            (Error::Bech32(a), Error::Bech32(b)) => a == b,
+            (Error::Io(a), Error::Io(b)) => a.kind() == b.kind(),
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Der(err) => Some(err),
+            Error::Base58(err) => Some(err),
+            Error::Bech32(err) => Some(err),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<DerError> for Error {
+    fn from(err: DerError) -> Self {
+        Error::Der(err)
+    }
+}
+
+impl From<Base58Error> for Error {
+    fn from(err: Base58Error) -> Self {
+        Error::Base58(err)
+    }
+}
+
+impl From<Bech32Error> for Error {
+    fn from(err: Bech32Error) -> Self {
+        Error::Bech32(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as _;
+    use std::io;
+
+    use super::Error;
+    use crate::ecc::signature::{DerError, Signature};
+
+    #[test]
+    fn display() {
+        assert_eq!(Error::NotOnCurve.to_string(), "Point is not on the curve");
+        assert_eq!(
+            Error::InvalidEncoding("bad prefix".to_string()).to_string(),
+            "Invalid encoding: bad prefix"
+        );
+    }
+
+    #[test]
+    fn wraps_sources() {
+        let err: Error = Signature::parse_der(&[0x30]).unwrap_err().into();
+        assert_eq!(err, Error::Der(DerError::InvalidLength(1)));
+        assert!(err.source().is_some());
+
+        let err = Error::from(io::Error::new(io::ErrorKind::UnexpectedEof, "eof"));
+        assert!(matches!(err, Error::Io(_)));
+        assert_eq!(err.to_string(), "eof");
+        assert_eq!(err, io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        assert_ne!(err, Error::NotOnCurve);
+        assert!(Error::NotOnCurve.source().is_none());
+    }
+}
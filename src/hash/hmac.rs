@@ -0,0 +1,167 @@
+use crate::ecc::constant_time::ConstantTimeEq;
+use crate::ecc::zeroize::Zeroize;
+use crate::hash::sha256::Sha256;
+use crate::hash::sha512::Sha512;
+use crate::hash::HashEngine;
+
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+// HMAC from RFC 2104: H((K ^ opad) || H((K ^ ipad) || message)), with K
+// hashed first if it's longer than a block and zero-padded to one otherwise.
+// Both padded keys are absorbed up front, so only the two hash states are kept.
+#[derive(Clone)]
+pub struct Hmac<H: HashEngine> {
+    inner: H,
+    outer: H,
+}
+
+impl<H: HashEngine> Hmac<H> {
+    pub fn new(key: &[u8]) -> Self {
+        let mut block = vec![0u8; H::BLOCK_SIZE];
+        if key.len() > H::BLOCK_SIZE {
+            let mut hasher = H::default();
+            hasher.update(key);
+            let digest = hasher.finalize();
+            block[..digest.as_ref().len()].copy_from_slice(digest.as_ref());
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+
+        let mut inner = H::default();
+        let mut outer = H::default();
+
+        block.iter_mut().for_each(|b| *b ^= IPAD);
+        inner.update(&block);
+        block.iter_mut().for_each(|b| *b ^= IPAD ^ OPAD);
+        outer.update(&block);
+        block.zeroize();
+
+        Self { inner, outer }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub fn finalize(self) -> H::Digest {
+        let mut outer = self.outer;
+        outer.update(self.inner.finalize().as_ref());
+        outer.finalize()
+    }
+
+    // Compares in constant time, so a forger can't learn how many leading
+    // bytes of a guessed tag were right
+    pub fn verify(self, tag: &H::Digest) -> bool
+    where
+        H::Digest: ConstantTimeEq,
+    {
+        self.finalize().ct_eq(tag)
+    }
+}
+
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new(key);
+    mac.update(data);
+    mac.finalize()
+}
+
+pub fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+    let mut mac = Hmac::<Sha512>::new(key);
+    mac.update(data);
+    mac.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hmac_sha256, hmac_sha512, Hmac};
+    use crate::hash::sha256::Sha256;
+    use crate::hash::sha512::Sha512;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    // Test cases 1, 2, 3, 6 and 7 from RFC 4231
+    #[test]
+    fn rfc4231_vectors() {
+        let long_key = [0xaa; 131];
+        let vectors: [(&[u8], &[u8], &str, &str); 5] = [
+            (
+                &[0x0b; 20],
+                b"Hi There",
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+                "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde\
+                 daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+            ),
+            (
+                b"Jefe",
+                b"what do ya want for nothing?",
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+                "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554\
+                 9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+            ),
+            (
+                &[0xaa; 20],
+                &[0xdd; 50],
+                "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+                "fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39\
+                 bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb",
+            ),
+            (
+                &long_key,
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+                "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352\
+                 6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+            ),
+            (
+                &long_key,
+                b"This is a test using a larger than block-size key and a larger than block-size data. \
+                  The key needs to be hashed before being used by the HMAC algorithm.",
+                "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+                "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944\
+                 b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58",
+            ),
+        ];
+
+        for (key, data, sha256, sha512) in vectors {
+            assert_eq!(hex(&hmac_sha256(key, data)), sha256);
+            assert_eq!(hex(&hmac_sha512(key, data)), sha512);
+        }
+    }
+
+    #[test]
+    fn incremental_matches_one_shot() {
+        let mut mac = Hmac::<Sha256>::new(b"Jefe");
+        mac.update(b"what do ya ");
+        mac.update(b"want for nothing?");
+
+        assert_eq!(
+            mac.finalize(),
+            hmac_sha256(b"Jefe", b"what do ya want for nothing?")
+        );
+    }
+
+    #[test]
+    fn verify_tag() {
+        let data = b"what do ya want for nothing?";
+        let mut tag = hmac_sha256(b"Jefe", data);
+
+        let mut mac = Hmac::<Sha256>::new(b"Jefe");
+        mac.update(data);
+        assert!(mac.clone().verify(&tag));
+
+        // Any flipped bit, including in the last byte, is rejected
+        tag[31] ^= 1;
+        assert!(!mac.verify(&tag));
+
+        let mut mac = Hmac::<Sha512>::new(b"Jefe");
+        mac.update(data);
+        assert!(mac.verify(&hmac_sha512(b"Jefe", data)));
+
+        let mut mac = Hmac::<Sha512>::new(b"Jeff");
+        mac.update(data);
+        assert!(!mac.verify(&hmac_sha512(b"Jefe", data)));
+    }
+}
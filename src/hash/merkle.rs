@@ -0,0 +1,155 @@
+use crate::hash::hash256;
+
+// Bitcoin's merkle root over txids that arrive one at a time, e.g. from a
+// block being decoded. Only one complete subtree per level waits for its right
+// sibling, so a block of n transactions needs O(log n) hashes, not O(n).
+#[derive(Debug, Clone, Default)]
+pub struct MerkleBuilder {
+    // pending[i] holds a subtree of 2^i txids whenever bit i of count is set
+    pending: Vec<Option<[u8; 32]>>,
+    count: u64,
+}
+
+impl MerkleBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // txid in internal byte order, as hashed, not the reversed display order
+    pub fn push(&mut self, txid: [u8; 32]) {
+        let mut hash = txid;
+        let mut level = 0;
+
+        // Adding one carries through every level that already has a subtree
+        while self.count & (1 << level) != 0 {
+            let left = self.pending[level].take().expect("bit set means pending");
+            hash = combine(&left, &hash);
+            level += 1;
+        }
+
+        if level == self.pending.len() {
+            self.pending.push(None);
+        }
+        self.pending[level] = Some(hash);
+        self.count += 1;
+    }
+
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    // None for no txids. An odd hash out on any level is paired with itself,
+    // as Bitcoin does, working upwards from the smallest pending subtree.
+    pub fn finalize(self) -> Option<[u8; 32]> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let mut count = self.count;
+        let mut level = count.trailing_zeros() as usize;
+        let mut hash = self.pending[level].expect("lowest set bit is pending");
+
+        while count != 1 << level {
+            hash = combine(&hash, &hash);
+            count += 1 << level;
+            level += 1;
+
+            // The carry merges with every larger subtree it runs into
+            while count & (1 << level) == 0 {
+                let left = self.pending[level].expect("cleared bit was pending");
+                hash = combine(&left, &hash);
+                level += 1;
+            }
+        }
+
+        Some(hash)
+    }
+}
+
+fn combine(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut data = [0u8; 64];
+    data[..32].copy_from_slice(left);
+    data[32..].copy_from_slice(right);
+
+    hash256(&data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{combine, MerkleBuilder};
+    use crate::hash::hash256;
+
+    fn txid(display: &str) -> [u8; 32] {
+        let mut bytes: [u8; 32] =
+            core::array::from_fn(|i| u8::from_str_radix(&display[2 * i..2 * i + 2], 16).unwrap());
+        bytes.reverse();
+        bytes
+    }
+
+    // Whole levels at a time, duplicating the last hash of odd levels
+    fn naive_root(txids: &[[u8; 32]]) -> [u8; 32] {
+        let mut level = txids.to_vec();
+        while level.len() > 1 {
+            if level.len() % 2 == 1 {
+                level.push(*level.last().unwrap());
+            }
+            level = level
+                .chunks_exact(2)
+                .map(|pair| combine(&pair[0], &pair[1]))
+                .collect();
+        }
+
+        level[0]
+    }
+
+    #[test]
+    fn block_100000() {
+        let mut builder = MerkleBuilder::new();
+        for id in [
+            "8c14f0db3df150123e6f3dbbf30f8b955a8249b62ac1d1ff16284aefa3d06d87",
+            "fff2525b8931402dd09222c50775608f75787bd2b87e56995a7bdd30f79702c4",
+            "6359f0868171b1d194cbee1af2f16ea598ae8fad666d9b012c8ed2b79a236ec4",
+            "e9a66845e05d5abc0ad04ec80f774a7e585c6e8db975962d069a522137b80c1d",
+        ] {
+            builder.push(txid(id));
+        }
+
+        assert_eq!(
+            builder.finalize(),
+            Some(txid(
+                "f3e94742aca4b5ef85488dc37c06c3282295ffec960994b2c0d5ac2a25a95766"
+            ))
+        );
+    }
+
+    #[test]
+    fn matches_naive_root() {
+        let txids: Vec<[u8; 32]> = (0..40u8).map(|i| hash256(&[i])).collect();
+
+        for n in 1..=txids.len() {
+            let mut builder = MerkleBuilder::new();
+            for txid in &txids[..n] {
+                builder.push(*txid);
+            }
+
+            assert_eq!(builder.len(), n as u64);
+            assert!(builder.pending.len() <= 6);
+            assert_eq!(builder.finalize(), Some(naive_root(&txids[..n])));
+        }
+    }
+
+    #[test]
+    fn single_and_empty() {
+        assert!(MerkleBuilder::new().is_empty());
+        assert_eq!(MerkleBuilder::new().finalize(), None);
+
+        // A lone coinbase is its own root
+        let mut builder = MerkleBuilder::new();
+        builder.push([7; 32]);
+        assert_eq!(builder.finalize(), Some([7; 32]));
+    }
+}
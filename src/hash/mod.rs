@@ -0,0 +1,178 @@
+pub mod hmac;
+pub mod merkle;
+pub mod ripemd160;
+pub mod sha256;
+pub mod sha512;
+pub mod siphash;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+
+use crate::hash::ripemd160::Ripemd160;
+use crate::hash::sha256::Sha256;
+
+// The incremental interface shared by the crate's hash functions, so
+// constructions like HMAC can be written once for all of them
+pub trait HashEngine: Clone + Default {
+    const BLOCK_SIZE: usize;
+    type Digest: AsRef<[u8]>;
+
+    fn update(&mut self, data: &[u8]);
+    fn finalize(self) -> Self::Digest;
+}
+
+// SHA256(SHA256(data)), used for txids, block hashes and legacy sighashes
+pub fn hash256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(&Sha256::digest(data))
+}
+
+// RIPEMD160(SHA256(data)), the 20-byte hash in P2PKH and P2SH scripts and
+// legacy addresses
+pub fn hash160(data: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(&Sha256::digest(data))
+}
+
+// Streaming hash256 for data that arrives in pieces, e.g. a transaction being
+// serialized straight into the hasher through io::Write.
+#[derive(Debug, Clone, Default)]
+pub struct Hash256Engine {
+    inner: Sha256,
+}
+
+impl Hash256Engine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub fn finalize(self) -> [u8; 32] {
+        Sha256::digest(&self.inner.finalize())
+    }
+}
+
+impl io::Write for Hash256Engine {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// BIP340 tagged hash: SHA256(SHA256(tag) || SHA256(tag) || data). The tag keeps
+// hashes computed for one purpose from colliding with another.
+pub fn tagged_hash(tag: &str, data: &[u8]) -> [u8; 32] {
+    let midstate = TAG_MIDSTATES.with(|cache| {
+        if let Some(midstate) = cache.borrow().get(tag) {
+            return *midstate;
+        }
+
+        let tag_hash = Sha256::digest(tag.as_bytes());
+        let mut prefix = Sha256::new();
+        prefix.update(&tag_hash);
+        prefix.update(&tag_hash);
+        let midstate = prefix.midstate().expect("two tag hashes fill one block");
+
+        cache.borrow_mut().insert(tag.to_string(), midstate);
+        midstate
+    });
+
+    let mut hasher = Sha256::from_midstate(midstate, sha256::BLOCK_SIZE as u64)
+        .expect("the prefix is one whole block");
+    hasher.update(data);
+
+    hasher.finalize()
+}
+
+thread_local! {
+    // The doubled tag hash is exactly one block, so each tag is compressed
+    // once per thread and every later hash resumes from its midstate
+    static TAG_MIDSTATES: RefCell<HashMap<String, [u8; 32]>> = RefCell::new(HashMap::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::{hash160, hash256, tagged_hash, Hash256Engine};
+    use crate::ecc::s256_point::S256Point;
+    use crate::hash::sha256::Sha256;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn tagged() {
+        assert_eq!(
+            hex(&tagged_hash("BIP0340/challenge", &[])),
+            "c216d352f5818b7b4beacd4ae0a26fe888080823d2a598856661bcd54f1b3713"
+        );
+
+        // TapLeaf hash of the script OP_TRUE with leaf version 0xc0
+        assert_eq!(
+            hex(&tagged_hash("TapLeaf", &[0xc0, 0x01, 0x51])),
+            "a85b2107f791b26a84e7586c28cec7cb61202ed3d01944d832500f363782d675"
+        );
+
+        assert_ne!(tagged_hash("TapLeaf", &[]), tagged_hash("TapBranch", &[]));
+
+        // Later calls resume from the tag's cached midstate
+        let data = [0x5a; 100];
+        let tag = Sha256::digest(b"TapLeaf");
+        assert_eq!(
+            tagged_hash("TapLeaf", &data),
+            Sha256::digest(&[&tag[..], &tag, &data].concat())
+        );
+    }
+
+    #[test]
+    fn double_sha256() {
+        assert_eq!(
+            hex(&hash256(&[])),
+            "5df6e0e2761359d30a8275058e299fcc0381534545f55cf43e41983f5d4c9456"
+        );
+        assert_eq!(
+            hex(&hash256(b"hello")),
+            "9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50"
+        );
+    }
+
+    #[test]
+    fn streaming() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+
+        let mut engine = Hash256Engine::new();
+        for chunk in data.chunks(5) {
+            engine.update(chunk);
+        }
+        assert_eq!(engine.finalize(), hash256(data));
+
+        let mut engine = Hash256Engine::new();
+        write!(engine, "The quick brown fox ").unwrap();
+        engine.write_all(b"jumps over the lazy dog").unwrap();
+        assert_eq!(engine.finalize(), hash256(data));
+    }
+
+    #[test]
+    fn hash160_vectors() {
+        assert_eq!(
+            hex(&hash160(&[])),
+            "b472a266d0bd89c13706a4132ccfb16f7c3b9fcb"
+        );
+
+        // Compressed SEC of the generator, whose hash160 is the P2PKH hash of
+        // private key 1
+        let g = S256Point::generator().sec_compressed().unwrap();
+        assert_eq!(
+            hex(&hash160(&g)),
+            "751e76e8199196d454941c45d1b3a323f1433bd6"
+        );
+    }
+}
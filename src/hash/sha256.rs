@@ -0,0 +1,299 @@
+// SHA-256 from FIPS 180-4
+
+use crate::error::Error;
+use crate::hash::HashEngine;
+
+// First 32 bits of the fractional parts of the cube roots of the first 64 primes
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+// First 32 bits of the fractional parts of the square roots of the first 8 primes
+const INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+pub const BLOCK_SIZE: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    buffer: [u8; BLOCK_SIZE],
+    buffered: usize,
+    // Total bytes hashed so far, for the length in the final padding
+    length: u64,
+}
+
+impl Sha256 {
+    pub fn new() -> Self {
+        Self {
+            state: INITIAL_STATE,
+            buffer: [0; BLOCK_SIZE],
+            buffered: 0,
+            length: 0,
+        }
+    }
+
+    pub fn digest(data: &[u8]) -> [u8; 32] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    // Resumes hashing from a chaining state saved by `midstate` after
+    // `length` bytes, which must be a whole number of blocks
+    pub fn from_midstate(midstate: [u8; 32], length: u64) -> Result<Self, Error> {
+        if !length.is_multiple_of(BLOCK_SIZE as u64) {
+            return Err(Error::InvalidInput(format!(
+                "Midstate length {} is not a multiple of the block size",
+                length
+            )));
+        }
+
+        let mut state = [0u32; 8];
+        for (word, chunk) in state.iter_mut().zip(midstate.chunks_exact(4)) {
+            *word = u32::from_be_bytes(chunk.try_into().unwrap());
+        }
+
+        Ok(Self {
+            state,
+            buffer: [0; BLOCK_SIZE],
+            buffered: 0,
+            length,
+        })
+    }
+
+    // The chaining state without padding, so a prefix shared by many messages
+    // (a tagged-hash tag, the common part of a sighash preimage) is only
+    // compressed once. None until a whole number of blocks has been hashed.
+    pub fn midstate(&self) -> Option<[u8; 32]> {
+        if self.buffered != 0 {
+            return None;
+        }
+
+        let mut result = [0u8; 32];
+        for (chunk, word) in result.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+
+        Some(result)
+    }
+
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+
+        // Top up a partially filled buffer first
+        if self.buffered > 0 {
+            let take = data.len().min(BLOCK_SIZE - self.buffered);
+            self.buffer[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+
+            if self.buffered < BLOCK_SIZE {
+                return;
+            }
+            let block = self.buffer;
+            compress(&mut self.state, &block);
+            self.buffered = 0;
+        }
+
+        let mut blocks = data.chunks_exact(BLOCK_SIZE);
+        for block in &mut blocks {
+            compress(&mut self.state, block.try_into().unwrap());
+        }
+
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    // Pads with 0x80, zeros and the message length in bits so the total is a
+    // multiple of the block size
+    pub fn finalize(mut self) -> [u8; 32] {
+        let bit_length = self.length.wrapping_mul(8);
+
+        let mut padding = [0u8; BLOCK_SIZE + 8];
+        padding[0] = 0x80;
+        let zeros = (BLOCK_SIZE + 56 - self.buffered - 1) % BLOCK_SIZE;
+        let pad_len = 1 + zeros;
+        padding[pad_len..pad_len + 8].copy_from_slice(&bit_length.to_be_bytes());
+        self.update(&padding[..pad_len + 8]);
+        debug_assert_eq!(self.buffered, 0);
+
+        let mut result = [0u8; 32];
+        for (chunk, word) in result.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+
+        result
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HashEngine for Sha256 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+    type Digest = [u8; 32];
+
+    fn update(&mut self, data: &[u8]) {
+        Sha256::update(self, data);
+    }
+
+    fn finalize(self) -> [u8; 32] {
+        Sha256::finalize(self)
+    }
+}
+
+fn compress(state: &mut [u32; 8], block: &[u8; BLOCK_SIZE]) {
+    // Message schedule
+    let mut w = [0u32; 64];
+    for (word, chunk) in w.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sha256;
+    use crate::error::Error;
+    use crate::hash::tagged_hash;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn nist_vectors() {
+        let vectors: [(&[u8], &str); 4] = [
+            (
+                b"",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                b"abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+            (
+                b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+                "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1",
+            ),
+        ];
+
+        for (data, expected) in vectors {
+            assert_eq!(hex(&Sha256::digest(data)), expected);
+        }
+    }
+
+    #[test]
+    fn million_a() {
+        let mut hasher = Sha256::new();
+        for _ in 0..1000 {
+            hasher.update(&[b'a'; 1000]);
+        }
+
+        assert_eq!(
+            hex(&hasher.finalize()),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+    }
+
+    #[test]
+    fn midstate_roundtrip() {
+        let tag = Sha256::digest(b"TapLeaf");
+        let mut prefix = Sha256::new();
+        prefix.update(&tag);
+        prefix.update(&tag);
+
+        // The doubled tag is exactly one block
+        let midstate = prefix.midstate().unwrap();
+        assert_eq!(prefix.length(), 64);
+
+        let mut resumed = Sha256::from_midstate(midstate, 64).unwrap();
+        resumed.update(&[0xc0, 0x01, 0x51]);
+        assert_eq!(
+            resumed.finalize(),
+            tagged_hash("TapLeaf", &[0xc0, 0x01, 0x51])
+        );
+
+        prefix.update(&[0xc0]);
+        assert!(prefix.midstate().is_none());
+        assert_eq!(
+            Sha256::from_midstate(midstate, 63).unwrap_err(),
+            Error::InvalidInput(
+                "Midstate length 63 is not a multiple of the block size".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn incremental_matches_one_shot() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+
+        // Every split point around the block and padding boundaries
+        for len in [0, 1, 55, 56, 63, 64, 65, 119, 120, 128, 1000] {
+            for split in [0, 1, 31, 63, 64, 65] {
+                let split = split.min(len);
+                let mut hasher = Sha256::new();
+                hasher.update(&data[..split]);
+                hasher.update(&data[split..len]);
+
+                assert_eq!(hasher.finalize(), Sha256::digest(&data[..len]));
+            }
+        }
+    }
+}
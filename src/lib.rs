@@ -1 +1,9 @@
-pub mod ecc;
\ No newline at end of file
+pub mod ecc;
+pub mod encoding;
+pub mod error;
+pub mod hash;
+pub mod taproot;
+
+pub use ecc::field_element::FieldElement;
+pub use ecc::point::Point;
+pub use error::Error;
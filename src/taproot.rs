@@ -0,0 +1,333 @@
+use crate::ecc::s256_point::S256Point;
+use crate::ecc::scalar::Scalar;
+use crate::ecc::secp256k1::N;
+use crate::ecc::uint::U256;
+use crate::ecc::x_only_public_key::{Parity, XOnlyPublicKey};
+use crate::error::Error;
+use crate::hash::tagged_hash;
+
+pub const TAPSCRIPT_LEAF_VERSION: u8 = 0xc0;
+
+// Control blocks can commit to at most 128 levels of the script tree
+const MAX_TREE_DEPTH: usize = 128;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TapLeaf {
+    pub version: u8,
+    pub script: Vec<u8>,
+}
+
+impl TapLeaf {
+    pub fn new(script: Vec<u8>, version: u8) -> Result<Self, Error> {
+        // The low bit of the first control block byte carries the output key parity
+        if version & 1 != 0 {
+            return Err(Error::InvalidInput(format!(
+                "Leaf version {:#04x} must be even",
+                version
+            )));
+        }
+
+        Ok(Self { version, script })
+    }
+
+    pub fn hash(&self) -> [u8; 32] {
+        leaf_hash(self.version, &self.script)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TapTree {
+    Leaf(TapLeaf),
+    Branch(Box<TapTree>, Box<TapTree>),
+}
+
+impl TapTree {
+    pub fn leaf(script: Vec<u8>, version: u8) -> Result<Self, Error> {
+        Ok(TapTree::Leaf(TapLeaf::new(script, version)?))
+    }
+
+    pub fn branch(left: TapTree, right: TapTree) -> Self {
+        TapTree::Branch(Box::new(left), Box::new(right))
+    }
+
+    pub fn merkle_root(&self) -> [u8; 32] {
+        match self {
+            TapTree::Leaf(leaf) => leaf.hash(),
+            TapTree::Branch(left, right) => branch_hash(&left.merkle_root(), &right.merkle_root()),
+        }
+    }
+
+    // Sibling hashes from the leaf up to the root, or None if the leaf isn't
+    // in this tree
+    pub fn merkle_path(&self, leaf: &TapLeaf) -> Option<Vec<[u8; 32]>> {
+        match self {
+            TapTree::Leaf(candidate) => (candidate == leaf).then(Vec::new),
+            TapTree::Branch(left, right) => {
+                if let Some(mut path) = left.merkle_path(leaf) {
+                    path.push(right.merkle_root());
+                    Some(path)
+                } else if let Some(mut path) = right.merkle_path(leaf) {
+                    path.push(left.merkle_root());
+                    Some(path)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    pub fn output_key(
+        &self,
+        internal_key: &XOnlyPublicKey,
+    ) -> Result<(XOnlyPublicKey, Parity), Error> {
+        tweak_public_key(internal_key, Some(&self.merkle_root()))
+    }
+
+    pub fn control_block(
+        &self,
+        internal_key: &XOnlyPublicKey,
+        leaf: &TapLeaf,
+    ) -> Result<ControlBlock, Error> {
+        let merkle_branch = self
+            .merkle_path(leaf)
+            .ok_or_else(|| Error::InvalidInput("Leaf is not part of the tree".to_string()))?;
+
+        if merkle_branch.len() > MAX_TREE_DEPTH {
+            return Err(Error::InvalidInput(format!(
+                "Leaf is deeper than {} levels",
+                MAX_TREE_DEPTH
+            )));
+        }
+
+        let (_, output_key_parity) = self.output_key(internal_key)?;
+
+        Ok(ControlBlock {
+            leaf_version: leaf.version,
+            output_key_parity,
+            internal_key: *internal_key,
+            merkle_branch,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ControlBlock {
+    pub leaf_version: u8,
+    pub output_key_parity: Parity,
+    pub internal_key: XOnlyPublicKey,
+    pub merkle_branch: Vec<[u8; 32]>,
+}
+
+impl ControlBlock {
+    pub fn serialize(&self) -> Vec<u8> {
+        let parity_bit = match self.output_key_parity {
+            Parity::Even => 0,
+            Parity::Odd => 1,
+        };
+
+        let mut result = Vec::with_capacity(33 + 32 * self.merkle_branch.len());
+        result.push(self.leaf_version | parity_bit);
+        result.extend_from_slice(&self.internal_key.serialize());
+        for hash in &self.merkle_branch {
+            result.extend_from_slice(hash);
+        }
+
+        result
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < 33
+            || !(bytes.len() - 33).is_multiple_of(32)
+            || bytes.len() > 33 + 32 * MAX_TREE_DEPTH
+        {
+            return Err(Error::InvalidEncoding(format!(
+                "Invalid control block length {}",
+                bytes.len()
+            )));
+        }
+
+        let output_key_parity = if bytes[0] & 1 == 0 {
+            Parity::Even
+        } else {
+            Parity::Odd
+        };
+
+        Ok(Self {
+            leaf_version: bytes[0] & 0xfe,
+            output_key_parity,
+            internal_key: XOnlyPublicKey::parse(&bytes[1..33])?,
+            merkle_branch: bytes[33..]
+                .chunks_exact(32)
+                .map(|chunk| chunk.try_into().unwrap())
+                .collect(),
+        })
+    }
+
+    // BIP341 script path check: rebuild the root from the script and the
+    // branch, tweak the internal key with it and compare to the output key
+    pub fn verify(&self, output_key: &XOnlyPublicKey, script: &[u8]) -> bool {
+        let mut hash = leaf_hash(self.leaf_version, script);
+        for sibling in &self.merkle_branch {
+            hash = branch_hash(&hash, sibling);
+        }
+
+        match tweak_public_key(&self.internal_key, Some(&hash)) {
+            Ok((key, parity)) => key == *output_key && parity == self.output_key_parity,
+            Err(_) => false,
+        }
+    }
+}
+
+// Q = P + int(hashTapTweak(P || root))G, with no root for key-path-only outputs
+pub fn tweak_public_key(
+    internal_key: &XOnlyPublicKey,
+    merkle_root: Option<&[u8; 32]>,
+) -> Result<(XOnlyPublicKey, Parity), Error> {
+    let mut data = internal_key.serialize().to_vec();
+    if let Some(root) = merkle_root {
+        data.extend_from_slice(root);
+    }
+
+    let tweak = U256::from_be_bytes(&tagged_hash("TapTweak", &data));
+    if tweak >= N {
+        return Err(Error::InvalidInput(
+            "Taproot tweak is not below the group order".to_string(),
+        ));
+    }
+
+    let output = internal_key.to_point() + S256Point::mul_generator(&Scalar::new(tweak));
+
+    XOnlyPublicKey::from_point(&output)
+}
+
+fn leaf_hash(version: u8, script: &[u8]) -> [u8; 32] {
+    let mut data = vec![version];
+    data.extend_from_slice(&compact_size(script.len()));
+    data.extend_from_slice(script);
+
+    tagged_hash("TapLeaf", &data)
+}
+
+// Children are sorted so the path doesn't need to record left or right
+fn branch_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    tagged_hash("TapBranch", &[first.as_slice(), second.as_slice()].concat())
+}
+
+fn compact_size(n: usize) -> Vec<u8> {
+    match n {
+        0..=0xfc => vec![n as u8],
+        0xfd..=0xffff => [&[0xfd], &(n as u16).to_le_bytes()[..]].concat(),
+        0x10000..=0xffff_ffff => [&[0xfe], &(n as u32).to_le_bytes()[..]].concat(),
+        _ => [&[0xff], &(n as u64).to_le_bytes()[..]].concat(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compact_size, tweak_public_key, ControlBlock, TapLeaf, TapTree, TAPSCRIPT_LEAF_VERSION,
+    };
+    use crate::ecc::x_only_public_key::XOnlyPublicKey;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn xonly(s: &str) -> XOnlyPublicKey {
+        XOnlyPublicKey::parse(&unhex(s)).unwrap()
+    }
+
+    #[test]
+    fn key_path_only() {
+        // BIP341 wallet test vector: no script tree
+        let internal = xonly("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d");
+        let (output, _) = tweak_public_key(&internal, None).unwrap();
+
+        assert_eq!(
+            hex(&output.serialize()),
+            "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343"
+        );
+    }
+
+    #[test]
+    fn single_leaf() {
+        // BIP341 wallet test vector: one tapscript leaf
+        let internal = xonly("187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27");
+        let script = unhex("20d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac");
+        let tree = TapTree::leaf(script.clone(), TAPSCRIPT_LEAF_VERSION).unwrap();
+
+        assert_eq!(
+            hex(&tree.merkle_root()),
+            "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21"
+        );
+
+        let (output, _) = tree.output_key(&internal).unwrap();
+        assert_eq!(
+            hex(&output.serialize()),
+            "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3"
+        );
+
+        let leaf = TapLeaf::new(script.clone(), TAPSCRIPT_LEAF_VERSION).unwrap();
+        let control_block = tree.control_block(&internal, &leaf).unwrap();
+        assert_eq!(
+            hex(&control_block.serialize()),
+            "c1187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27"
+        );
+        assert!(control_block.verify(&output, &script));
+    }
+
+    #[test]
+    fn control_blocks_for_every_leaf() {
+        let internal = xonly("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d");
+        let scripts: Vec<Vec<u8>> = (0u8..4).map(|i| vec![0x51 + i, 0x87]).collect();
+
+        // ((A, B), (C, D)) with an extra leaf hanging off the right: (((A, B), (C, D)), E)
+        let leaf =
+            |script: &Vec<u8>| TapTree::leaf(script.clone(), TAPSCRIPT_LEAF_VERSION).unwrap();
+        let tree = TapTree::branch(
+            TapTree::branch(
+                TapTree::branch(leaf(&scripts[0]), leaf(&scripts[1])),
+                TapTree::branch(leaf(&scripts[2]), leaf(&scripts[3])),
+            ),
+            leaf(&vec![0x00]),
+        );
+        let (output, _) = tree.output_key(&internal).unwrap();
+
+        for script in &scripts {
+            let tap_leaf = TapLeaf::new(script.clone(), TAPSCRIPT_LEAF_VERSION).unwrap();
+            let control_block = tree.control_block(&internal, &tap_leaf).unwrap();
+
+            assert_eq!(control_block.merkle_branch.len(), 3);
+            assert!(control_block.verify(&output, script));
+            assert!(!control_block.verify(&output, &[0x00]));
+
+            let parsed = ControlBlock::parse(&control_block.serialize()).unwrap();
+            assert_eq!(parsed, control_block);
+        }
+
+        let missing = TapLeaf::new(vec![0x6a], TAPSCRIPT_LEAF_VERSION).unwrap();
+        assert!(tree.control_block(&internal, &missing).is_err());
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        assert!(TapLeaf::new(vec![], 0xc1).is_err());
+        assert!(ControlBlock::parse(&[0xc0; 32]).is_err());
+        assert!(ControlBlock::parse(&[0xc0; 34]).is_err());
+    }
+
+    #[test]
+    fn compact_sizes() {
+        assert_eq!(compact_size(0xfc), vec![0xfc]);
+        assert_eq!(compact_size(0xfd), vec![0xfd, 0xfd, 0x00]);
+        assert_eq!(compact_size(0x10000), vec![0xfe, 0x00, 0x00, 0x01, 0x00]);
+    }
+}